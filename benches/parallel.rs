@@ -0,0 +1,48 @@
+// A tick of a busy 1024x1024 board on one thread and on the whole thread
+// pool, with the `parallel` feature:
+//
+//     cargo bench --features parallel --bench parallel
+//
+// Two-state rules step packed on a single thread, so the board runs a
+// Generations rule, which takes the parallel path.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rayon::ThreadPoolBuilder;
+use wasm_game_of_life::Universe;
+
+const SIZE: u32 = 1024;
+
+fn soup() -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(SIZE).unwrap();
+    universe.set_height(SIZE).unwrap();
+    universe.set_rule("B3/S23/C3").unwrap();
+    universe.set_seed(53);
+    universe.randomize_with_density(0.3).unwrap();
+    // Past the first burst, when dying cells fill the board.
+    universe.tick_n(20);
+    universe
+}
+
+fn threads(c: &mut Criterion) {
+    let universe = soup();
+    let mut group = c.benchmark_group("tick_1024");
+    let all = rayon::current_num_threads();
+    for (name, threads) in [("one_thread", 1), ("all_threads", all)] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new(name, threads), &universe, |b, universe| {
+            b.iter_batched_ref(
+                || universe.clone(),
+                |universe| pool.install(|| universe.tick()),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, threads);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+// Ticking the same 512x512 soups as a dense `Universe` and as a
+// `SparseUniverse`, from nearly empty to a tenth alive, to find where the
+// sparse board stops paying off.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use wasm_game_of_life::{SparseUniverse, Universe};
+
+const SIZE: u32 = 512;
+
+fn soup(density: f64) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(SIZE).unwrap();
+    universe.set_height(SIZE).unwrap();
+    universe.set_seed(48);
+    universe.randomize_with_density(density).unwrap();
+    universe
+}
+
+fn sparse_against_dense(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_against_dense");
+    for percent in [0.5, 1.0, 2.0, 4.0, 8.0, 10.0] {
+        let dense = soup(percent / 100.0);
+        group.bench_with_input(BenchmarkId::new("dense", percent), &dense, |b, dense| {
+            b.iter_batched_ref(|| dense.clone(), Universe::tick, BatchSize::LargeInput)
+        });
+        group.bench_with_input(BenchmarkId::new("sparse", percent), &dense, |b, dense| {
+            b.iter_batched_ref(
+                || SparseUniverse::from_dense(dense).unwrap(),
+                SparseUniverse::tick,
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sparse_against_dense);
+criterion_main!(benches);
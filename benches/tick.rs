@@ -0,0 +1,50 @@
+// Ticking random soups of a few sizes natively, the way the browser would
+// tick them each frame.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use wasm_game_of_life::Universe;
+
+fn soup(size: u32, rule: &str) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(size).unwrap();
+    universe.set_height(size).unwrap();
+    universe.set_rule(rule).unwrap();
+    universe.set_seed(54);
+    universe.randomize_with_density(0.3).unwrap();
+    // Run long enough to fill the step-back ring, so the ticks timed are
+    // the ones that reuse its boards rather than the first few.
+    universe.tick_n(64);
+    universe
+}
+
+fn tick(c: &mut Criterion) {
+    // Life steps packed, and a Generations rule a byte per cell.
+    for (name, rule) in [("tick", "B3/S23"), ("tick_generations", "B3/S23/C3")] {
+        let mut group = c.benchmark_group(name);
+        for size in [64, 256, 1024] {
+            let universe = soup(size, rule);
+            group.bench_with_input(
+                BenchmarkId::from_parameter(size),
+                &universe,
+                |b, universe| {
+                    b.iter_batched_ref(|| universe.clone(), Universe::tick, BatchSize::LargeInput)
+                },
+            );
+        }
+        group.finish();
+    }
+}
+
+fn tick_n(c: &mut Criterion) {
+    let universe = soup(256, "B3/S23");
+    c.bench_function("tick_n_100_256", |b| {
+        b.iter_batched_ref(
+            || universe.clone(),
+            |universe| universe.tick_n(100),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, tick, tick_n);
+criterion_main!(benches);
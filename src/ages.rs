@@ -0,0 +1,91 @@
+// How many generations each live cell has been alive, for coloring cells by
+// age.
+//
+// Cells written through `set_state` start again at 1, and whole new boards,
+// such as after resizing, start every live cell at 1. Any other edit is
+// caught up with before the next generation or read: a live cell without an
+// age becomes 1 and a dead cell's age drops to 0.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, ALIVE};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop tracking cell ages. Off by default, since it costs two
+    /// bytes per cell and a pass over the board each generation. Turning it
+    /// on counts every live cell as newborn.
+    pub fn set_track_ages(&mut self, enabled: bool) {
+        self.ages = enabled.then(|| fresh_ages(&self.cells));
+    }
+
+    /// Pointer to the ages, one `u16` per cell in row-major order: 0 for a
+    /// dead or dying cell, 1 for a cell born in the last generation or
+    /// placed since, and one more for each generation it survived, up to
+    /// 65535. Null while ages aren't tracked.
+    ///
+    /// Like `cells`, read it again after every call that may change the
+    /// board.
+    pub fn ages(&mut self) -> *const u16 {
+        self.sync_ages();
+        self.ages
+            .as_ref()
+            .map_or(std::ptr::null(), |ages| ages.as_ptr())
+    }
+
+    /// The number of ages behind `ages`: one per cell, or 0 while ages
+    /// aren't tracked.
+    pub fn ages_len(&self) -> u32 {
+        self.ages.as_ref().map_or(0, |_| self.cells.len() as u32)
+    }
+}
+
+impl Universe {
+    // Bring the ages in line with cells changed without them.
+    pub(crate) fn sync_ages(&mut self) {
+        let Some(ages) = &mut self.ages else {
+            return;
+        };
+        if ages.len() != self.cells.len() {
+            *ages = fresh_ages(&self.cells);
+            return;
+        }
+        for (age, &state) in ages.iter_mut().zip(&self.cells) {
+            if state != ALIVE {
+                *age = 0;
+            } else if *age == 0 {
+                *age = 1;
+            }
+        }
+    }
+
+    // Age every cell by the generation just run, given the ages were in
+    // line with the cells before it.
+    pub(crate) fn advance_ages(&mut self) {
+        let Some(ages) = &mut self.ages else {
+            return;
+        };
+        for (age, &state) in ages.iter_mut().zip(&self.cells) {
+            *age = if state == ALIVE {
+                age.saturating_add(1)
+            } else {
+                0
+            };
+        }
+    }
+
+    // Start the ages again for a whole new board.
+    pub(crate) fn reset_ages(&mut self) {
+        if let Some(ages) = &mut self.ages {
+            ages.clear();
+            ages.extend(self.cells.iter().map(|&state| u16::from(state == ALIVE)));
+        }
+    }
+}
+
+fn fresh_ages(cells: &[u8]) -> Vec<u16> {
+    cells
+        .iter()
+        .map(|&state| u16::from(state == ALIVE))
+        .collect()
+}
@@ -0,0 +1,90 @@
+// Langton's ants walking over the grid.
+//
+// On each tick an ant on a dead cell turns right and one on a live cell turns
+// left; it then flips the cell it stands on and steps forward, wrapping at
+// the edges.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{toggled, Universe, ALIVE};
+
+// An ant's position and the direction it faces, numbered clockwise from 0 for
+// north.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ant {
+    pub row: u32,
+    pub col: u32,
+    pub direction: u8,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Add a Langton's ant facing `direction`: 0 for north, 1 for east, 2 for
+    /// south or 3 for west. Positions wrap at the edges.
+    ///
+    /// While any ants exist, each `tick` moves every ant once before the
+    /// automaton runs, or instead of it when `set_ants_only` is enabled.
+    pub fn add_ant(&mut self, row: u32, col: u32, direction: u8) -> Result<(), JsValue> {
+        if direction >= 4 {
+            return Err(JsValue::from_str(&format!(
+                "Invalid ant direction {}; expected 0 (north), 1 (east), 2 (south) or 3 (west)",
+                direction
+            )));
+        }
+        self.ants.push(Ant {
+            row: row % self.height,
+            col: col % self.width,
+            direction,
+        });
+        Ok(())
+    }
+
+    /// Every ant as a flat `[row0, col0, direction0, row1, ...]` array, in
+    /// the order they were added.
+    pub fn ants(&self) -> js_sys::Uint32Array {
+        let flat: Vec<u32> = self
+            .ants
+            .iter()
+            .flat_map(|ant| [ant.row, ant.col, u32::from(ant.direction)])
+            .collect();
+        js_sys::Uint32Array::from(&flat[..])
+    }
+
+    pub fn remove_ants(&mut self) {
+        self.ants.clear();
+    }
+
+    /// When enabled, ticks only move the ants and the automaton is paused.
+    /// Off by default, so ants walk through a running Life universe.
+    pub fn set_ants_only(&mut self, ants_only: bool) {
+        self.ants_only = ants_only;
+    }
+}
+
+impl Universe {
+    pub(crate) fn move_ants(&mut self) {
+        for i in 0..self.ants.len() {
+            let mut ant = self.ants[i];
+            // Ants may be left outside the grid after it shrinks.
+            ant.row %= self.height;
+            ant.col %= self.width;
+
+            let idx = self.get_index(ant.row, ant.col);
+            let turn = if self.cells[idx] == ALIVE { 3 } else { 1 };
+            ant.direction = (ant.direction + turn) % 4;
+            self.cells[idx] = toggled(self.cells[idx]);
+            self.mark_edited(idx);
+            self.count_change(idx, self.cells[idx] == ALIVE);
+            self.bit_change(idx, self.cells[idx]);
+
+            let (row, col) = (ant.row, ant.col);
+            (ant.row, ant.col) = match ant.direction {
+                0 => ((row + self.height - 1) % self.height, col),
+                1 => (row, (col + 1) % self.width),
+                2 => ((row + 1) % self.height, col),
+                _ => (row, (col + self.width - 1) % self.width),
+            };
+            self.ants[i] = ant;
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Ready-made automata that can be selected by name.
+
+use wasm_bindgen::prelude::*;
+
+use crate::ltl::LtlRule;
+use crate::map_rule::MapRule;
+use crate::rules::Rule;
+use crate::{immigration, wireworld, Universe, DEAD};
+
+// The transition function `tick` applies. Life covers every Life-like and
+// Generations rule, using the universe's current `Rule`, and Elementary holds
+// a Wolfram rule number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Automaton {
+    Life,
+    Wireworld,
+    Immigration,
+    Elementary(u8),
+    LargerThanLife(LtlRule),
+    Map(MapRule),
+}
+
+// Brian's Brain: off cells with exactly two live neighbours turn on, live
+// cells always start dying and dying cells turn off.
+const BRIANS_BRAIN: Rule = Rule {
+    birth: 1 << 2,
+    survival: 0,
+    states: 3,
+};
+
+impl Automaton {
+    // How many cell states boards under this automaton use, given the
+    // universe's rule for Life.
+    pub fn states(self, rule: Rule) -> u8 {
+        match self {
+            Automaton::Life => rule.states,
+            Automaton::Wireworld => wireworld::STATES,
+            Automaton::Immigration => immigration::STATES,
+            _ => 2,
+        }
+    }
+}
+
+const NAMES: [&str; 4] = ["life", "brians_brain", "wireworld", "immigration"];
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to a named automaton: `"life"` for Conway's Game of Life,
+    /// `"brians_brain"` for the three-state Brian's Brain, where live cells
+    /// always pass through a dying state before turning off, `"wireworld"`
+    /// for Wireworld circuits or `"immigration"` for two-player Life.
+    ///
+    /// Names are case insensitive. Cells in states the new automaton does
+    /// not use are cleared.
+    pub fn set_automaton(&mut self, name: &str) -> Result<(), JsValue> {
+        match name.trim().to_lowercase().as_str() {
+            "life" => self.apply_rule(Rule::CONWAY),
+            "brians_brain" => self.apply_rule(BRIANS_BRAIN),
+            "wireworld" => self.apply_automaton(Automaton::Wireworld, wireworld::STATES),
+            "immigration" => self.apply_automaton(Automaton::Immigration, immigration::STATES),
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Unknown automaton '{}'; available automata: {}",
+                    name.trim(),
+                    NAMES.join(", ")
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    // Switch the transition function, replacing any custom rule and killing
+    // any cells in states beyond the `states` the new automaton uses.
+    pub(crate) fn apply_automaton(&mut self, automaton: Automaton, states: u8) {
+        self.custom_rule = None;
+        for idx in 0..self.cells.len() {
+            if self.state(idx) >= states {
+                self.set_state(idx, DEAD);
+            }
+        }
+        self.automaton = automaton;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::empty;
+    use crate::ALIVE;
+
+    #[test]
+    fn brians_brain_sends_out_a_wavefront() {
+        let mut universe = empty(20, 20);
+        universe.set_automaton("brians_brain").unwrap();
+        universe.set_pattern("block", 9, 9).unwrap();
+        let mut last = universe.cells.clone();
+        for t in 1..=6u32 {
+            universe.tick();
+            // The front moves out a cell a tick on every side...
+            assert_eq!(
+                universe.occupied_bounds(),
+                Some((9 - t, 10 + t, 9 - t, 10 + t))
+            );
+            assert_eq!(universe.population(), 4 * t + 4);
+            // ...leaving last tick's live cells dying just behind it.
+            for (idx, &cell) in universe.cells.iter().enumerate() {
+                assert_eq!(cell == 2, last[idx] == ALIVE, "cell {idx} at tick {t}");
+            }
+            last = universe.cells.clone();
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Standard base64 (RFC 4648) encoding and decoding, along with the URL-safe
+// variant that uses `-` and `_` and leaves out the padding.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// Encode bytes as base64 with `=` padding.
+pub fn encode(bytes: &[u8]) -> String {
+    encode_with(bytes, ALPHABET, true)
+}
+
+// Decode base64, with or without `=` padding. Leftover bits that don't make a
+// whole byte are dropped.
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    decode_with(text, ALPHABET)
+}
+
+// Encode bytes as URL-safe base64 without padding.
+pub fn encode_url(bytes: &[u8]) -> String {
+    encode_with(bytes, URL_ALPHABET, false)
+}
+
+// Decode URL-safe base64, as with `decode`.
+pub fn decode_url(text: &str) -> Result<Vec<u8>, String> {
+    decode_with(text, URL_ALPHABET)
+}
+
+fn encode_with(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(alphabet[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else if pad {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode_with(text: &str, alphabet: &[u8; 64]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for ch in text.trim_end_matches('=').chars() {
+        let value = match alphabet.iter().position(|&c| c as char == ch) {
+            Some(value) => value as u32,
+            None => return Err(format!("'{}' is not a base64 character", ch)),
+        };
+        buffer = buffer << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,168 @@
+// A terminal playground for the universe, built with the `cli` feature:
+//
+//     cargo run --no-default-features --features cli --bin life
+//
+// The universe fills the terminal and follows it when it is resized. Arrow
+// keys move the cursor, space toggles the cell under it, `p` places the
+// selected pattern there and `n` selects the next one. Enter starts and
+// stops the simulation, `s` steps once while stopped, `+` and `-` change
+// the speed, `c` clears, `r` randomizes and `q` quits.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::{cursor, queue, style, terminal};
+use wasm_game_of_life::Universe;
+
+// The patterns `n` cycles through.
+const PATTERNS: [&str; 8] = [
+    "glider",
+    "lwss",
+    "r_pentomino",
+    "acorn",
+    "diehard",
+    "pulsar",
+    "pentadecathlon",
+    "gosper_glider_gun",
+];
+
+const SPEEDS: [u32; 8] = [1, 2, 5, 10, 20, 30, 60, 120];
+
+struct Playground {
+    universe: Universe,
+    cursor: (u32, u32),
+    pattern: usize,
+    speed: usize,
+    running: bool,
+}
+
+// Puts the terminal back the way it was, even after a panic.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(io::stdout(), terminal::EnterAlternateScreen)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(io::stdout(), terminal::LeaveAlternateScreen, cursor::Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> io::Result<()> {
+    let _terminal = RawTerminal::enter()?;
+    let mut playground = Playground::new(terminal::size()?);
+    let mut last_tick = Instant::now();
+
+    loop {
+        playground.draw(&mut io::stdout())?;
+
+        let interval = Duration::from_secs(1) / SPEEDS[playground.speed];
+        let wait = if playground.running {
+            interval.saturating_sub(last_tick.elapsed())
+        } else {
+            interval
+        };
+        if event::poll(wait)? {
+            match event::read()? {
+                Event::Key(key) if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) => {
+                    return Ok(())
+                }
+                Event::Key(key) if key.kind != KeyEventKind::Release => playground.handle_key(key),
+                Event::Resize(columns, rows) => {
+                    playground.fit((columns, rows));
+                    crossterm::execute!(io::stdout(), terminal::Clear(terminal::ClearType::All))?;
+                }
+                _ => {}
+            }
+        }
+
+        if playground.running && last_tick.elapsed() >= interval {
+            playground.universe.tick();
+            last_tick = Instant::now();
+        }
+    }
+}
+
+impl Playground {
+    fn new(size: (u16, u16)) -> Playground {
+        let mut playground = Playground {
+            universe: Universe::new(),
+            cursor: (0, 0),
+            pattern: 0,
+            speed: 3,
+            running: false,
+        };
+        playground.fit(size);
+        playground.universe.clear();
+        playground
+    }
+
+    // Resize the universe to fill a terminal of the given size, keeping its
+    // cells, with the bottom row left for the status line.
+    fn fit(&mut self, (columns, rows): (u16, u16)) {
+        let width = u32::from(columns).max(1);
+        let height = u32::from(rows).saturating_sub(1).max(1);
+        // Both dimensions are at least 1 and far below the cell limit.
+        let _ = self.universe.resize(width, height, "top_left");
+        self.cursor = (self.cursor.0.min(height - 1), self.cursor.1.min(width - 1));
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        let (width, height) = (self.universe.width(), self.universe.height());
+        let (row, col) = self.cursor;
+        match key.code {
+            KeyCode::Up => self.cursor.0 = (row + height - 1) % height,
+            KeyCode::Down => self.cursor.0 = (row + 1) % height,
+            KeyCode::Left => self.cursor.1 = (col + width - 1) % width,
+            KeyCode::Right => self.cursor.1 = (col + 1) % width,
+            KeyCode::Char(' ') => self.universe.toggle_cell(row, col),
+            KeyCode::Char('p') => {
+                // Every name in `PATTERNS` is built in, so this can't fail.
+                let _ = self.universe.set_pattern(PATTERNS[self.pattern], row, col);
+            }
+            KeyCode::Char('n') => self.pattern = (self.pattern + 1) % PATTERNS.len(),
+            KeyCode::Enter => self.running = !self.running,
+            KeyCode::Char('s') if !self.running => self.universe.tick(),
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1)
+            }
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Char('c') => self.universe.clear(),
+            KeyCode::Char('r') => self.universe.randomize(),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, cursor::Hide)?;
+        for (row, line) in self.universe.to_string().lines().enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16), style::Print(line))?;
+        }
+
+        let status = format!(
+            " {} | {} gen/s | pattern: {} | arrows move, space toggles, p places, n next pattern, \
+             enter runs, s steps, +/- speed, c clears, r randomizes, q quits",
+            if self.running { "running" } else { "paused" },
+            SPEEDS[self.speed],
+            PATTERNS[self.pattern],
+        );
+        let columns = self.universe.width() as usize;
+        let status: String = status.chars().take(columns).collect();
+        queue!(
+            out,
+            cursor::MoveTo(0, self.universe.height() as u16),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            style::Print(status),
+            cursor::MoveTo(self.cursor.1 as u16, self.cursor.0 as u16),
+            cursor::Show,
+        )?;
+        out.flush()
+    }
+}
@@ -0,0 +1,108 @@
+// What lies beyond the edges of the universe.
+
+use std::ops::Range;
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    // Opposite edges are joined, so patterns leaving one side come back on
+    // the other.
+    Torus,
+    // Everything past the edges is permanently dead.
+    Dead,
+    // The edges reflect, so the cell past an edge mirrors the cell just
+    // inside it.
+    Mirror,
+}
+
+impl Boundary {
+    // The grid position of a neighbour at `position` along an axis of
+    // `size` cells, or `None` when it lies past a dead edge.
+    pub fn neighbor(self, position: i64, size: u32) -> Option<u32> {
+        match self {
+            Boundary::Torus => Some(position.rem_euclid(i64::from(size)) as u32),
+            Boundary::Dead => u32::try_from(position).ok().filter(|&p| p < size),
+            Boundary::Mirror => {
+                // Reflecting at both edges repeats every two widths.
+                let size = i64::from(size);
+                let folded = position.rem_euclid(2 * size);
+                Some(if folded < size {
+                    folded
+                } else {
+                    2 * size - 1 - folded
+                } as u32)
+            }
+        }
+    }
+
+    // The grid position where a cell placed at `position` lands, or `None`
+    // when it falls off the edge. Only the torus wraps placed cells.
+    pub fn place(self, position: i64, size: u32) -> Option<u32> {
+        match self {
+            Boundary::Torus => self.neighbor(position, size),
+            _ => u32::try_from(position).ok().filter(|&p| p < size),
+        }
+    }
+
+    // The offsets into a run of `length` cells placed from `start` that
+    // land on the grid, each on a different cell: at most one lap of a
+    // torus, and only those inside the edges otherwise.
+    pub fn span(self, start: i64, length: u32, size: u32) -> Range<u32> {
+        match self {
+            Boundary::Torus => 0..length.min(size),
+            _ => {
+                let (length, size) = (i64::from(length), i64::from(size));
+                (-start).clamp(0, length) as u32..(size - start).clamp(0, length) as u32
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Choose what lies beyond the edges: `"torus"` (the default) joins
+    /// opposite edges so patterns wrap around, `"dead"` treats every cell
+    /// past the edges as dead, so gliders crash into the walls, and
+    /// `"mirror"` reflects the cells along each edge, so the cell past an
+    /// edge copies the one just inside it.
+    ///
+    /// With a dead or mirror boundary, patterns placed across an edge are
+    /// cut off instead of wrapping.
+    pub fn set_boundary(&mut self, kind: &str) -> Result<(), JsValue> {
+        self.boundary = match kind.trim().to_lowercase().as_str() {
+            "torus" => Boundary::Torus,
+            "dead" => Boundary::Dead,
+            "mirror" => Boundary::Mirror,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Unknown boundary '{}'; expected 'torus', 'dead' or 'mirror'",
+                    kind.trim()
+                )))
+            }
+        };
+        Ok(())
+    }
+}
+
+impl Universe {
+    // Index of the cell `row` rows and `col` columns from a start position,
+    // or `None` when it falls off the edge.
+    pub(crate) fn offset_index(
+        &self,
+        start_row: i64,
+        start_col: i64,
+        row: u32,
+        col: u32,
+    ) -> Option<usize> {
+        let r = self
+            .boundary
+            .place(start_row + i64::from(row), self.height)?;
+        let c = self
+            .boundary
+            .place(start_col + i64::from(col), self.width)?;
+        Some(self.get_index(r, c))
+    }
+}
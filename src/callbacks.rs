@@ -0,0 +1,331 @@
+// JavaScript functions the universe calls as it runs.
+//
+// Callbacks are called once the generation they report on is finished, so
+// an exception thrown by one is logged and leaves the universe as it would
+// be without the callback.
+
+use wasm_bindgen::prelude::*;
+
+use crate::events::TickEvents;
+use crate::Universe;
+
+// The function `tick`, `tick_n` and `tick_until_stable` report each
+// generation or batch to.
+#[derive(Clone, Debug, Default)]
+pub struct OnTick {
+    callback: Option<js_sys::Function>,
+    batched: bool,
+    // What the generations not yet reported did, in batched mode.
+    generations: u32,
+    births: u32,
+    deaths: u32,
+}
+
+// The function `tick`, `tick_n` and `tick_until_stable` call when the board
+// settles.
+#[derive(Clone, Debug, Default)]
+pub struct OnSettled {
+    callback: Option<js_sys::Function>,
+    max_period: u32,
+    // Set once the callback has been called for the board as it is, until
+    // the board is edited.
+    fired: bool,
+}
+
+// Callbacks and the event target, set aside by `take_callbacks`.
+pub struct SetAside {
+    on_tick: OnTick,
+    on_settled: OnSettled,
+    tick_events: Option<TickEvents>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Call `callback` after every generation run by `tick`, `tick_n` or
+    /// `tick_until_stable`, replacing any callback already set. It is passed an
+    /// object with the new `generation`, the `population` and the `births` and
+    /// `deaths` in that generation, and `generations`, which is 1.
+    ///
+    /// The universe is busy while the callback runs, so calling its methods
+    /// from the callback throws an error; use the numbers passed in.
+    /// Errors thrown by the callback are logged to the console.
+    pub fn set_on_tick(&mut self, callback: &js_sys::Function) {
+        self.on_tick.callback = Some(callback.clone());
+    }
+
+    /// Call the tick callback once per call to `tick_n` or `tick_until_stable`
+    /// rather than once per generation, with `generations` set to how many it
+    /// ran and the births and deaths summed over all of them. Off by default.
+    pub fn set_on_tick_batched(&mut self, batched: bool) {
+        self.on_tick.batched = batched;
+    }
+
+    /// Stop calling the tick callback and let it go.
+    pub fn clear_on_tick(&mut self) {
+        self.on_tick.callback = None;
+    }
+
+    /// Call `callback` once when a generation run by `tick`, `tick_n` or
+    /// `tick_until_stable` leaves the board dead or cycling with a period of at
+    /// most `max_period`, as found by `detect_period`, replacing any callback
+    /// already set. It is passed an object with the `kind`, which is
+    /// `"extinct"`, `"stable"` or `"oscillating"`, the `period`, which is 0 for
+    /// an extinct board, and the `generation`.
+    ///
+    /// It isn't called again until the board is edited, loaded or rolled
+    /// back, or the rule changes, and then settles once more. Like the tick
+    /// callback, it can't use the universe and errors it throws are logged.
+    pub fn set_on_settled(&mut self, callback: &js_sys::Function, max_period: u32) {
+        self.on_settled = OnSettled {
+            callback: Some(callback.clone()),
+            max_period,
+            fired: false,
+        };
+    }
+
+    /// Stop watching for the board to settle and let the callback go.
+    pub fn clear_on_settled(&mut self) {
+        self.on_settled = OnSettled::default();
+    }
+}
+
+impl Universe {
+    // Report the generation just run to the callbacks and event target.
+    pub(crate) fn after_generation(&mut self) {
+        self.count_tick();
+        self.check_settled();
+        self.count_tick_event();
+    }
+
+    // Report what a call that ran generations did, once it has finished.
+    pub(crate) fn after_ticks(&mut self) {
+        self.finish_ticks();
+        self.dispatch_tick_event();
+    }
+
+    // Report the generation just run, or add it to the batch.
+    fn count_tick(&mut self) {
+        let changes = self.changes;
+        let on_tick = &mut self.on_tick;
+        let Some(callback) = &on_tick.callback else {
+            return;
+        };
+        if !on_tick.batched {
+            let stats = Stats {
+                generation: self.generation,
+                population: changes.births + changes.survivals,
+                generations: 1,
+                births: changes.births,
+                deaths: changes.deaths,
+            };
+            stats.report(callback);
+            return;
+        }
+        on_tick.generations += 1;
+        on_tick.births = on_tick.births.saturating_add(changes.births);
+        on_tick.deaths = on_tick.deaths.saturating_add(changes.deaths);
+    }
+
+    // Report the batch of generations just run, if any were held back.
+    fn finish_ticks(&mut self) {
+        if self.on_tick.generations == 0 {
+            return;
+        }
+        let stats = Stats {
+            generation: self.generation,
+            population: self.population(),
+            generations: std::mem::take(&mut self.on_tick.generations),
+            births: std::mem::take(&mut self.on_tick.births),
+            deaths: std::mem::take(&mut self.on_tick.deaths),
+        };
+        if let Some(callback) = &self.on_tick.callback {
+            stats.report(callback);
+        }
+    }
+
+    // Set the callbacks and event target aside, such as while replaying
+    // generations already reported.
+    pub(crate) fn take_callbacks(&mut self) -> SetAside {
+        SetAside {
+            on_tick: std::mem::take(&mut self.on_tick),
+            on_settled: std::mem::take(&mut self.on_settled),
+            tick_events: self.tick_events.take(),
+        }
+    }
+
+    // Put back what `take_callbacks` set aside, watching for the board,
+    // which has since changed, to settle again.
+    pub(crate) fn restore_callbacks(&mut self, set_aside: SetAside) {
+        self.on_tick = set_aside.on_tick;
+        self.on_settled = OnSettled {
+            fired: false,
+            ..set_aside.on_settled
+        };
+        self.tick_events = set_aside.tick_events;
+    }
+
+    // Before running generations, re-arm the settled callback if the board
+    // has changed since the last one ran.
+    pub(crate) fn watch_settled(&mut self) {
+        if self.on_settled.callback.is_some() {
+            self.rearm_settled();
+        }
+    }
+
+    // The longest period the settled callback looks for, or 0 if none is
+    // set, so that `detect_period` keeps enough of the record for it.
+    pub(crate) fn settled_max_period(&self) -> u32 {
+        if self.on_settled.callback.is_some() {
+            self.on_settled.max_period
+        } else {
+            0
+        }
+    }
+
+    // Check whether the board has changed since the last generation ran,
+    // other than by running. If so, it may settle again, and its record
+    // starts from how it is now, so the first generation can already show
+    // it settled.
+    fn rearm_settled(&mut self) {
+        if self.history_is_current() {
+            return;
+        }
+        self.on_settled.fired = false;
+        self.detect_period(self.on_settled.max_period);
+    }
+
+    // The kind of settling and the period, if the generation just run left
+    // the board settled for the first time since it was last edited. Once
+    // it has, nothing more is returned until `rearm_settled` sees an edit.
+    fn newly_settled(&mut self) -> Option<(&'static str, i32)> {
+        if self.on_settled.fired {
+            return None;
+        }
+        let settled = match self.detect_period(self.on_settled.max_period) {
+            0 => return None,
+            -1 => ("extinct", 0),
+            1 => ("stable", 1),
+            period => ("oscillating", period),
+        };
+        self.on_settled.fired = true;
+        Some(settled)
+    }
+
+    // Call the settled callback if the generation just run left the board
+    // newly settled.
+    fn check_settled(&mut self) {
+        if self.on_settled.callback.is_none() {
+            return;
+        }
+        let Some((kind, period)) = self.newly_settled() else {
+            return;
+        };
+        let Some(callback) = &self.on_settled.callback else {
+            return;
+        };
+        let event = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&event, &"kind".into(), &kind.into());
+        let _ = js_sys::Reflect::set(&event, &"period".into(), &period.into());
+        let _ = js_sys::Reflect::set(
+            &event,
+            &"generation".into(),
+            &(self.generation as f64).into(),
+        );
+        if let Err(error) = callback.call1(&JsValue::NULL, &event) {
+            web_sys::console::error_2(&"Settled callback threw:".into(), &error);
+        }
+    }
+}
+
+// What the tick callback is told.
+struct Stats {
+    generation: u64,
+    population: u32,
+    generations: u32,
+    births: u32,
+    deaths: u32,
+}
+
+impl Stats {
+    fn report(&self, callback: &js_sys::Function) {
+        let object = js_sys::Object::new();
+        for (key, value) in [
+            ("generation", self.generation as f64),
+            ("population", f64::from(self.population)),
+            ("generations", f64::from(self.generations)),
+            ("births", f64::from(self.births)),
+            ("deaths", f64::from(self.deaths)),
+        ] {
+            let _ = js_sys::Reflect::set(&object, &key.into(), &value.into());
+        }
+        if let Err(error) = callback.call1(&JsValue::NULL, &object) {
+            web_sys::console::error_2(&"Tick callback threw:".into(), &error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnSettled;
+    use crate::testing::empty;
+    use crate::Universe;
+
+    // Run a generation as `tick` does with a settled callback set, which
+    // can't be made natively, returning what it would have been told.
+    fn tick(universe: &mut Universe) -> Option<(&'static str, i32)> {
+        universe.rearm_settled();
+        universe.tick();
+        universe.newly_settled()
+    }
+
+    fn watching(max_period: u32) -> OnSettled {
+        OnSettled {
+            max_period,
+            ..OnSettled::default()
+        }
+    }
+
+    #[test]
+    fn fires_once_and_again_after_an_edit() {
+        let mut universe = empty(12, 12);
+        universe.on_settled = watching(2);
+        universe.set_pattern("blinker", 2, 2).unwrap();
+        universe.set_pattern("block", 8, 8).unwrap();
+
+        assert_eq!(tick(&mut universe), None);
+        assert_eq!(tick(&mut universe), Some(("oscillating", 2)));
+        for _ in 0..5 {
+            assert_eq!(tick(&mut universe), None);
+        }
+
+        // Without its middle cell the blinker dies, leaving the block.
+        universe.toggle_cell(2, 3);
+        assert_eq!(tick(&mut universe), None);
+        assert_eq!(tick(&mut universe), Some(("stable", 1)));
+        assert_eq!(tick(&mut universe), None);
+        universe.clear();
+        assert_eq!(tick(&mut universe), Some(("extinct", 0)));
+        assert_eq!(tick(&mut universe), None);
+    }
+
+    #[test]
+    fn changing_the_rule_or_rolling_back_rearms() {
+        let mut universe = empty(12, 12);
+        universe.on_settled = watching(1);
+        universe.enable_checkpoints(1, 10);
+        universe.set_pattern("block", 4, 4).unwrap();
+        assert_eq!(tick(&mut universe), Some(("stable", 1)));
+        assert_eq!(tick(&mut universe), None);
+
+        // A block survives under B36/S23 too, but is watched afresh.
+        universe.set_rule("B36/S23").unwrap();
+        assert_eq!(tick(&mut universe), Some(("stable", 1)));
+        assert_eq!(tick(&mut universe), None);
+
+        let callbacks = universe.take_callbacks();
+        universe.rollback_to(2).unwrap();
+        universe.restore_callbacks(callbacks);
+        assert_eq!(tick(&mut universe), Some(("stable", 1)));
+        assert_eq!(tick(&mut universe), None);
+    }
+}
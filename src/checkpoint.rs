@@ -0,0 +1,138 @@
+// Saving the board every so many generations on long runs, so any earlier
+// generation can be returned to by going back to the checkpoint before it
+// and running forward again.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::undo::SavedBoard;
+use crate::Universe;
+
+#[derive(Clone, Debug, Default)]
+pub struct Checkpoints {
+    // Generations between checkpoints; none are taken while this is 0.
+    interval: u32,
+    capacity: usize,
+    // Oldest first.
+    boards: VecDeque<SavedBoard>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Save the board whenever the generation reaches a multiple of
+    /// `interval`, keeping the latest `max_checkpoints` and dropping the
+    /// oldest first. The current board is saved straight away. Off by
+    /// default; 0 for either turns checkpoints off and forgets them.
+    ///
+    /// Checkpoints are packed a bit per cell unless some cells are in a
+    /// third state, so a 256x256 board costs 8KB each.
+    pub fn enable_checkpoints(&mut self, interval: u32, max_checkpoints: u32) {
+        let checkpoints = &mut self.checkpoints;
+        checkpoints.interval = interval;
+        checkpoints.capacity = if interval == 0 {
+            0
+        } else {
+            max_checkpoints as usize
+        };
+        while checkpoints.boards.len() > checkpoints.capacity {
+            checkpoints.boards.pop_front();
+        }
+        if checkpoints.capacity > 0 {
+            self.push_checkpoint();
+        }
+    }
+
+    /// The generations checkpoints are kept for, oldest first.
+    pub fn checkpoints(&self) -> js_sys::Array {
+        self.checkpoints
+            .boards
+            .iter()
+            .map(|board| JsValue::from(board.generation))
+            .collect()
+    }
+
+    /// Go back to `generation` by restoring the latest checkpoint at or
+    /// before it and running forward from there under the current rule.
+    /// Checkpoints after `generation` are dropped, as ticking on will take
+    /// them again.
+    ///
+    /// The generations run again are not added to the population history or
+    /// kept for `step_back` a second time, and edits made since the
+    /// checkpoint are not replayed. Throws an error if `generation` is ahead
+    /// of the current one or no checkpoint is that old.
+    pub fn rollback_to(&mut self, generation: u64) -> Result<(), JsValue> {
+        if generation > self.generation {
+            return Err(JsValue::from_str(&format!(
+                "Generation {} is ahead of the current generation {}",
+                generation, self.generation
+            )));
+        }
+        let boards = &mut self.checkpoints.boards;
+        let Some(found) = boards
+            .iter()
+            .rposition(|board| board.generation <= generation)
+        else {
+            return Err(JsValue::from_str(&format!(
+                "No checkpoint is kept at or before generation {}",
+                generation
+            )));
+        };
+        let steps = u32::try_from(generation - boards[found].generation).map_err(|_| {
+            JsValue::from_str(&format!(
+                "Generation {} is too far past the nearest checkpoint",
+                generation
+            ))
+        })?;
+        boards.truncate(found + 1);
+        let board = boards[found].clone();
+
+        self.remember();
+        self.load_board(&board);
+        // The replayed generations were recorded when they first ran, so
+        // they aren't saved for stepping back or added to the population
+        // history again, nor reported to the callbacks. The period record
+        // starts afresh, as what it holds came after them.
+        let rewind = self.pause_rewind();
+        let population_history = std::mem::take(&mut self.population_history);
+        self.forget_history();
+        #[cfg(feature = "web")]
+        let callbacks = self.take_callbacks();
+        // Once the board stops changing, the rest can be skipped.
+        if self.run_until_settled(steps) < steps {
+            self.generation = generation;
+        }
+        #[cfg(feature = "web")]
+        self.restore_callbacks(callbacks);
+        self.population_history = population_history;
+        self.resume_rewind(rewind);
+        Ok(())
+    }
+}
+
+impl Universe {
+    // Take a checkpoint after a generation if it falls on the interval.
+    pub(crate) fn save_checkpoint(&mut self) {
+        let interval = u64::from(self.checkpoints.interval);
+        if self.checkpoints.capacity > 0 && self.generation.is_multiple_of(interval) {
+            self.push_checkpoint();
+        }
+    }
+
+    fn push_checkpoint(&mut self) {
+        let board = self.save_board();
+        let checkpoints = &mut self.checkpoints;
+        // Later checkpoints are from a run since undone or restarted.
+        while checkpoints
+            .boards
+            .back()
+            .is_some_and(|last| last.generation >= board.generation)
+        {
+            checkpoints.boards.pop_back();
+        }
+        if checkpoints.boards.len() == checkpoints.capacity {
+            checkpoints.boards.pop_front();
+        }
+        checkpoints.boards.push_back(board);
+    }
+}
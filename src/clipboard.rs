@@ -0,0 +1,48 @@
+// A single copied selection held inside the universe, so it can be
+// transformed and stamped repeatedly without round-tripping through
+// JavaScript strings.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{PlacementMode, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Copy a rectangle of cells into the clipboard, wrapping at the edges
+    /// and replacing any previous contents.
+    pub fn copy_selection(&mut self, row: u32, col: u32, height: u32, width: u32) {
+        self.clipboard = Some(self.region_pattern(row, col, height, width));
+    }
+
+    /// Rotate the clipboard contents 90 degrees clockwise.
+    pub fn rotate_clipboard(&mut self) {
+        if let Some(copied) = &self.clipboard {
+            self.clipboard = Some(copied.transformed(1, false, false));
+        }
+    }
+
+    /// Mirror the clipboard contents left to right when `horizontal` is set,
+    /// otherwise top to bottom.
+    pub fn flip_clipboard(&mut self, horizontal: bool) {
+        if let Some(copied) = &self.clipboard {
+            self.clipboard = Some(copied.transformed(0, horizontal, !horizontal));
+        }
+    }
+
+    /// Paste the clipboard with its top-left corner at the given position,
+    /// overwriting the whole rectangle and wrapping at the edges.
+    ///
+    /// Returns false, leaving the universe unchanged, when the clipboard is
+    /// empty.
+    pub fn paste_clipboard(&mut self, row: u32, col: u32) -> bool {
+        match self.clipboard.take() {
+            Some(copied) => {
+                self.remember();
+                self.place(&copied, row, col, PlacementMode::Replace);
+                self.clipboard = Some(copied);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Finding the separate objects on the board: clusters of live cells joined
+// through any of their eight neighbours.
+//
+// On a torus a cluster can run off one edge and carry on at the other, and
+// is still one object, so its bounding box is the smallest one that wraps
+// around the edges to take it in, like a wrapping selection.
+
+use wasm_bindgen::prelude::*;
+
+use crate::boundary::Boundary;
+use crate::{Universe, ALIVE};
+
+// Marks a cell that belongs to no component.
+const NO_COMPONENT: u32 = u32::MAX;
+
+// The components of the board as it was when they were found.
+#[derive(Clone, Debug)]
+pub struct Labels {
+    width: u32,
+    boundary: Boundary,
+    cells: Vec<u8>,
+    // The component each cell belongs to.
+    ids: Vec<u32>,
+    components: Vec<Component>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Component {
+    count: u32,
+    row: u32,
+    col: u32,
+    height: u32,
+    width: u32,
+    centroid_row: f64,
+    centroid_col: f64,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The clusters of live cells joined through any of their eight
+    /// neighbours, each as an object holding its `id`, its `count` of
+    /// cells, its bounding box as `row`, `col`, `height` and `width`, ready
+    /// to pass to `select`, and its `centroid_row` and `centroid_col`, the
+    /// mean position of its cells.
+    ///
+    /// Ids count up from 0 in the order of each component's first cell,
+    /// reading row by row. On a torus, clusters that cross an edge are one
+    /// component, and their bounding box and centroid wrap around it, so
+    /// the box may run past the right or bottom edge and carry on at the
+    /// other side.
+    pub fn components(&mut self) -> js_sys::Array {
+        let result = js_sys::Array::new();
+        for (id, component) in self.labels().components.iter().enumerate() {
+            let object = js_sys::Object::new();
+            for (key, value) in [
+                ("id", id as f64),
+                ("count", f64::from(component.count)),
+                ("row", f64::from(component.row)),
+                ("col", f64::from(component.col)),
+                ("height", f64::from(component.height)),
+                ("width", f64::from(component.width)),
+                ("centroid_row", component.centroid_row),
+                ("centroid_col", component.centroid_col),
+            ] {
+                let _ = js_sys::Reflect::set(&object, &key.into(), &value.into());
+            }
+            result.push(&object);
+        }
+        result
+    }
+
+    /// The id of the component the cell at (`row`, `col`) belongs to, as
+    /// listed by `components`, or `undefined` if the cell isn't alive. Ids
+    /// hold until the board next changes; after that the board's components
+    /// are found afresh and may be numbered differently. Throws an error if
+    /// the cell is outside the universe.
+    pub fn component_at(&mut self, row: u32, col: u32) -> Result<Option<u32>, JsValue> {
+        let idx = self.checked_index(row, col)?;
+        let id = self.labels().ids[idx];
+        Ok((id != NO_COMPONENT).then_some(id))
+    }
+}
+
+impl Universe {
+    // The board's components, found again if the board has changed since
+    // they were last found.
+    fn labels(&mut self) -> &Labels {
+        let current = self.labels.as_ref().is_some_and(|labels| {
+            labels.width == self.width
+                && labels.boundary == self.boundary
+                && labels.cells == self.cells
+        });
+        if !current {
+            self.labels = Some(self.find_components());
+        }
+        self.labels.as_ref().expect("labels were just found")
+    }
+
+    // Label every live cell with its component, filling out from the first
+    // unlabelled live cell in each row in turn.
+    fn find_components(&self) -> Labels {
+        let mut ids = vec![NO_COMPONENT; self.cells.len()];
+        let mut components = Vec::new();
+        let mut stack = Vec::new();
+        let mut members = Vec::new();
+        for start in 0..self.cells.len() {
+            if self.cells[start] != ALIVE || ids[start] != NO_COMPONENT {
+                continue;
+            }
+            let id = components.len() as u32;
+            ids[start] = id;
+            stack.push(start);
+            members.clear();
+            while let Some(idx) = stack.pop() {
+                let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+                members.push((row, col));
+                let (r, c) = (i64::from(row), i64::from(col));
+                for (dr, dc) in NEIGHBOR_OFFSETS {
+                    let Some(r) = self.boundary.place(r + dr, self.height) else {
+                        continue;
+                    };
+                    let Some(c) = self.boundary.place(c + dc, self.width) else {
+                        continue;
+                    };
+                    let next = self.get_index(r, c);
+                    if self.cells[next] == ALIVE && ids[next] == NO_COMPONENT {
+                        ids[next] = id;
+                        stack.push(next);
+                    }
+                }
+            }
+
+            let wraps = self.boundary == Boundary::Torus;
+            let (row, height, centroid_row) =
+                extent(members.iter().map(|&(row, _)| row), self.height, wraps);
+            let (col, width, centroid_col) =
+                extent(members.iter().map(|&(_, col)| col), self.width, wraps);
+            components.push(Component {
+                count: members.len() as u32,
+                row,
+                col,
+                height,
+                width,
+                centroid_row,
+                centroid_col,
+            });
+        }
+        Labels {
+            width: self.width,
+            boundary: self.boundary,
+            cells: self.cells.clone(),
+            ids,
+            components,
+        }
+    }
+}
+
+const NEIGHBOR_OFFSETS: [(i64, i64); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+// The first position, length and mean position of a component's cells
+// along an axis of `size` cells. When the axis wraps, the span starts just
+// past the widest run of positions the component leaves empty, so it may
+// carry on past the end and round to the start.
+fn extent(positions: impl Iterator<Item = u32> + Clone, size: u32, wraps: bool) -> (u32, u32, f64) {
+    let mut occupied: Vec<u32> = positions.clone().collect();
+    occupied.sort_unstable();
+    occupied.dedup();
+    let (first, last) = (occupied[0], occupied[occupied.len() - 1]);
+
+    // The gap from the last position round to the first is preferred, so
+    // a component that doesn't cross the edge keeps its plain span.
+    let (mut start, mut length) = (first, last - first + 1);
+    if wraps {
+        let mut widest = size - length;
+        for pair in occupied.windows(2) {
+            let gap = pair[1] - pair[0] - 1;
+            if gap > widest {
+                widest = gap;
+                (start, length) = (pair[1], size - gap);
+            }
+        }
+    }
+
+    let (mut sum, mut count) = (0.0, 0.0);
+    for position in positions {
+        sum += f64::from((position + size - start) % size);
+        count += 1.0;
+    }
+    let mean = f64::from(start) + sum / count;
+    let mean = if mean >= f64::from(size) {
+        mean - f64::from(size)
+    } else {
+        mean
+    };
+    (start, length, mean)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::empty;
+    use crate::ALIVE;
+
+    #[test]
+    fn clusters_across_both_seams_are_one_component() {
+        // A block split over all four corners, and a blinker standing on
+        // its own in the middle.
+        let mut universe = empty(10, 8);
+        for (row, col) in [(0, 0), (0, 9), (7, 0), (7, 9), (3, 4), (3, 5), (3, 6)] {
+            let idx = universe.get_index(row, col);
+            universe.set_state(idx, ALIVE);
+        }
+
+        let labels = universe.labels();
+        assert_eq!(labels.components.len(), 2);
+        let block = labels.components[0];
+        assert_eq!(block.count, 4);
+        assert_eq!(
+            (block.row, block.col, block.height, block.width),
+            (7, 9, 2, 2)
+        );
+        assert_eq!((block.centroid_row, block.centroid_col), (7.5, 9.5));
+        let blinker = labels.components[1];
+        assert_eq!(
+            (blinker.row, blinker.col, blinker.height, blinker.width),
+            (3, 4, 1, 3)
+        );
+        for (row, col) in [(0, 0), (0, 9), (7, 0), (7, 9)] {
+            assert_eq!(universe.component_at(row, col).unwrap(), Some(0));
+        }
+        assert_eq!(universe.component_at(3, 5).unwrap(), Some(1));
+        assert_eq!(universe.component_at(4, 5).unwrap(), None);
+
+        // Without the wrap, each corner is a cluster of its own.
+        universe.set_boundary("dead").unwrap();
+        assert_eq!(universe.labels().components.len(), 5);
+    }
+}
@@ -0,0 +1,301 @@
+// Live neighbour counts kept up to date between generations.
+//
+// Rather than counting every cell's neighbours afresh each tick, the
+// per-cell loop reads them from a table that follows the cells as they
+// change: each cell born adds one to each of its neighbours' counts and
+// each cell that dies takes one away. A step lists the cells it changed and
+// applies them once the new generation is in place, and edits between
+// ticks apply theirs as they go, so only changed cells cost anything.
+// Changes too broad to list, and changes of size, neighbourhood or
+// boundary, have the table counted again from scratch before the next step.
+// Two-state rules step on packed cells without it, letting it go until
+// another rule needs it counted again.
+
+use crate::boundary::Boundary;
+use crate::neighborhood::Neighborhood;
+use crate::{Universe, ALIVE};
+
+// The arrangement of neighbours the counts were computed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountLayout {
+    neighborhood: Neighborhood,
+    boundary: Boundary,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NeighborCounts {
+    // What the counts were computed for, or `None` once they are out of
+    // date.
+    pub layout: Option<CountLayout>,
+    // The number of live neighbours of each cell.
+    pub counts: Vec<u8>,
+    // The cells the last step brought to life or killed, kept to reuse its
+    // allocation.
+    pub changed: Vec<u32>,
+}
+
+impl Universe {
+    /// Check the neighbour counts kept between generations against a fresh
+    /// count of the current cells, panicking at the first that differs.
+    /// Only built for tests and debug builds.
+    #[cfg(any(test, debug_assertions))]
+    pub fn verify_counts(&mut self) {
+        self.sync_counts();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                debug_assert_eq!(
+                    self.neighbor_counts.counts[idx],
+                    self.live_neighbor_count(row, col),
+                    "neighbour count of ({row}, {col})"
+                );
+            }
+        }
+    }
+
+    fn count_layout(&self) -> CountLayout {
+        CountLayout {
+            neighborhood: self.neighborhood,
+            boundary: self.boundary,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    // Count every cell's neighbours from scratch if the counts are out of
+    // date, such as after a change of neighbourhood, boundary or size.
+    pub(crate) fn sync_counts(&mut self) {
+        let layout = self.count_layout();
+        if self.neighbor_counts.layout == Some(layout) {
+            return;
+        }
+        let mut counts = std::mem::take(&mut self.neighbor_counts.counts);
+        self.count_all(&mut counts);
+        self.neighbor_counts.counts = counts;
+        self.neighbor_counts.layout = Some(layout);
+    }
+
+    // Count every cell's neighbours into `counts`. Cells away from the
+    // edges find theirs at fixed steps from their own index; only the
+    // border goes through the boundary to find the cells it wraps to.
+    fn count_all(&self, counts: &mut Vec<u8>) {
+        counts.clear();
+        counts.resize(self.cells.len(), 0);
+        let (width, height) = (self.width, self.height);
+
+        let steps: Vec<isize> = self
+            .neighborhood
+            .offsets()
+            .iter()
+            .map(|&(delta_row, delta_col)| delta_row as isize * width as isize + delta_col as isize)
+            .collect();
+        for row in (1..height.saturating_sub(1)).filter(|_| width > 2) {
+            let start = self.get_index(row, 1);
+            let end = self.get_index(row, width - 1);
+            for (idx, count) in (start..end).zip(&mut counts[start..end]) {
+                *count = steps
+                    .iter()
+                    .map(|&step| u8::from(self.cells[idx.wrapping_add_signed(step)] == ALIVE))
+                    .sum();
+            }
+        }
+
+        for row in [0, height - 1] {
+            for col in 0..width {
+                counts[self.get_index(row, col)] = self.live_neighbor_count(row, col);
+            }
+        }
+        for row in 1..height.saturating_sub(1) {
+            for col in [0, width - 1] {
+                counts[self.get_index(row, col)] = self.live_neighbor_count(row, col);
+            }
+        }
+    }
+
+    // Apply a cell at `idx` coming to life or dying to its neighbours'
+    // counts, unless they are out of date and will be counted afresh.
+    pub(crate) fn count_change(&mut self, idx: usize, alive: bool) {
+        if self.neighbor_counts.layout != Some(self.count_layout()) {
+            return;
+        }
+        let mut counts = std::mem::take(&mut self.neighbor_counts.counts);
+        self.adjust_neighbors(&mut counts, idx, alive);
+        self.neighbor_counts.counts = counts;
+    }
+
+    // Apply the cells a step brought to life or killed, now that the new
+    // generation is in place.
+    pub(crate) fn count_changes(&mut self, changed: &[u32]) {
+        let mut counts = std::mem::take(&mut self.neighbor_counts.counts);
+        for &idx in changed {
+            let alive = self.cells[idx as usize] == ALIVE;
+            self.adjust_neighbors(&mut counts, idx as usize, alive);
+        }
+        self.neighbor_counts.counts = counts;
+    }
+
+    fn adjust_neighbors(&self, counts: &mut [u8], idx: usize, alive: bool) {
+        // The neighbourhoods are symmetric, even where the mirror boundary
+        // folds them, so the cells that count this one are exactly its own
+        // neighbours.
+        let width = self.width as usize;
+        let (row, col) = ((idx / width) as u32, (idx % width) as u32);
+        for neighbor in self.neighbor_indices(row, col) {
+            if alive {
+                counts[neighbor] += 1;
+            } else {
+                counts[neighbor] -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::Rng;
+    use crate::testing::soup;
+    use crate::{Universe, ALIVE};
+
+    // Tick with random edits in between, checking the counts were kept up
+    // to date cell by cell rather than counted afresh. Two-state rules
+    // don't keep counts, so Life runs as a Generations rule.
+    fn check_edits_and_ticks(universe: &mut Universe, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let (height, width) = (universe.height, universe.width);
+        for _ in 0..60 {
+            universe.tick();
+            assert!(universe.neighbor_counts.layout.is_some());
+            for _ in 0..5 {
+                let row = (rng.next_f64() * f64::from(height)) as u32;
+                let col = (rng.next_f64() * f64::from(width)) as u32;
+                universe.toggle_cell(row, col);
+            }
+            universe.set_pattern("glider", 3, 3).unwrap();
+            assert!(universe.neighbor_counts.layout.is_some());
+            universe.verify_counts();
+        }
+    }
+
+    #[test]
+    fn counts_follow_ticks_and_edits() {
+        for (seed, boundary) in [(1, "torus"), (2, "dead"), (3, "mirror")] {
+            for neighborhood in ["moore", "von_neumann"] {
+                let mut universe = soup(37, 29, 0.35, seed);
+                universe.set_rule("B3/S23/C3").unwrap();
+                universe.set_boundary(boundary).unwrap();
+                universe.set_neighborhood(neighborhood).unwrap();
+                check_edits_and_ticks(&mut universe, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn counts_follow_other_automata() {
+        for (seed, automaton) in [(4, "brians_brain"), (5, "immigration"), (6, "wireworld")] {
+            let mut universe = soup(40, 30, 0.3, seed);
+            universe.set_automaton(automaton).unwrap();
+            check_edits_and_ticks(&mut universe, seed);
+        }
+    }
+
+    #[test]
+    fn counts_survive_wholesale_changes() {
+        let mut universe = soup(40, 30, 0.35, 7);
+        universe.tick();
+        universe.shift(5, -9);
+        universe.verify_counts();
+        universe.tick();
+        universe.mirror(true);
+        universe.tick();
+        universe.verify_counts();
+        universe.randomize();
+        universe.tick();
+        universe.verify_counts();
+        universe.resize(50, 20, "center").unwrap();
+        universe.tick();
+        universe.verify_counts();
+        universe.undo();
+        universe.tick();
+        universe.verify_counts();
+        universe.set_elementary_rule(30);
+        universe.tick_n(3);
+        universe.set_automaton("life").unwrap();
+        universe.tick();
+        universe.verify_counts();
+        universe.clear();
+        universe.tick();
+        universe.verify_counts();
+    }
+
+    #[test]
+    fn counts_match_modulo_wrapping_on_random_boards() {
+        // From two cells across; on a single row or column the modulo loop
+        // skips the cell itself twice over.
+        let mut sizes = Rng::new(9);
+        for seed in 0..300 {
+            let width = 2 + (sizes.next_f64() * 40.0) as u32;
+            let height = 2 + (sizes.next_f64() * 40.0) as u32;
+            let universe = soup(width, height, 0.4, seed);
+            let mut counts = Vec::new();
+            universe.count_all(&mut counts);
+            for row in 0..height {
+                for col in 0..width {
+                    let mut count = 0;
+                    for delta_row in [height - 1, 0, 1] {
+                        for delta_col in [width - 1, 0, 1] {
+                            if (delta_row, delta_col) == (0, 0) {
+                                continue;
+                            }
+                            let r = (row + delta_row) % height;
+                            let c = (col + delta_col) % width;
+                            count += u8::from(universe.cells[(r * width + c) as usize] == ALIVE);
+                        }
+                    }
+                    let idx = universe.get_index(row, col);
+                    assert_eq!(
+                        counts[idx], count,
+                        "{width}x{height} board from seed {seed}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interior_counts_match_the_boundary_counts() {
+        let mut sizes = Rng::new(10);
+        for seed in 0..300 {
+            let width = 1 + (sizes.next_f64() * 24.0) as u32;
+            let height = 1 + (sizes.next_f64() * 24.0) as u32;
+            let mut universe = soup(width, height, 0.4, seed);
+            universe
+                .set_boundary(["torus", "dead", "mirror"][seed as usize % 3])
+                .unwrap();
+            universe
+                .set_neighborhood(["moore", "von_neumann"][seed as usize / 3 % 2])
+                .unwrap();
+            let mut counts = Vec::new();
+            universe.count_all(&mut counts);
+            for row in 0..height {
+                for col in 0..width {
+                    let idx = universe.get_index(row, col);
+                    assert_eq!(counts[idx], universe.live_neighbor_count(row, col));
+                }
+            }
+        }
+    }
+
+    // The check is a `debug_assert`, so there is nothing to notice in a
+    // release test run.
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "neighbour count of (2, 5)")]
+    fn verify_counts_notices_a_wrong_count() {
+        let mut universe = soup(20, 20, 0.3, 8);
+        universe.verify_counts();
+        universe.neighbor_counts.counts[45] += 1;
+        universe.verify_counts();
+    }
+}
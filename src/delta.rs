@@ -0,0 +1,53 @@
+// Ticking while recording which cells changed, so a renderer can repaint
+// only those.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, DEAD};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Advance one generation like `tick` and return the cells whose state
+    /// changed as a flat `[row0, col0, row1, col1, ...]` array.
+    ///
+    /// If the universe grew during the tick the old positions no longer
+    /// apply, so every cell that isn't dead is listed instead; clear the
+    /// canvas before painting them.
+    pub fn tick_delta(&mut self) -> js_sys::Uint32Array {
+        // Ants flip cells before the rule runs, so the spare buffer doesn't
+        // hold the cells as they were; `tick` keeps a copy instead.
+        let walking = !self.ants.is_empty();
+        let front = self.cells.as_ptr();
+        let size = (self.width, self.height);
+
+        self.tick();
+
+        self.delta.clear();
+        let width = self.width as usize;
+        if (self.width, self.height) != size {
+            for (idx, _) in self
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell != DEAD)
+            {
+                self.delta
+                    .extend([(idx / width) as u32, (idx % width) as u32]);
+            }
+        } else if walking || self.cells.as_ptr() != front {
+            // Without ants, a tick that didn't swap buffers changed nothing.
+            let before = if walking {
+                &self.delta_base
+            } else {
+                &self.next
+            };
+            for (idx, (now, was)) in self.cells.iter().zip(before).enumerate() {
+                if now != was {
+                    self.delta
+                        .extend([(idx / width) as u32, (idx % width) as u32]);
+                }
+            }
+        }
+        js_sys::Uint32Array::from(&self.delta[..])
+    }
+}
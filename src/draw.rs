@@ -0,0 +1,265 @@
+// Drawing the board onto a 2D canvas.
+//
+// Cells are `cell_size` pixels square. With grid lines on, each cell has a
+// one-pixel line before it and the board ends with one more, as in
+// `index.html`; without them cells sit edge to edge.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+#[cfg(feature = "web")]
+use crate::{ALIVE, DEAD};
+
+// Grid lines are dark grey unless set otherwise.
+pub const DEFAULT_GRID_COLOR: &str = "#333333";
+
+// The colors `draw` uses, as CSS color strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DrawStyle {
+    pub alive: String,
+    pub dead: String,
+    pub dying: String,
+    // No grid lines are drawn while this is `None`.
+    pub grid: Option<String>,
+}
+
+impl Default for DrawStyle {
+    fn default() -> Self {
+        DrawStyle {
+            alive: "#00FF00".to_string(),
+            dead: "#000000".to_string(),
+            dying: "#006600".to_string(),
+            grid: Some(DEFAULT_GRID_COLOR.to_string()),
+        }
+    }
+}
+
+// What `draw_delta` last painted, to find the cells that have changed
+// since.
+#[cfg(feature = "web")]
+#[derive(Clone, Debug)]
+pub struct Drawn {
+    width: u32,
+    cell_size: f64,
+    style: DrawStyle,
+    cells: Vec<u8>,
+}
+
+// A cell's position, as returned by `Universe::cell_at`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellCoord {
+    pub row: u32,
+    pub col: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set the colors `draw` fills cells with, as CSS colors. Dying cells
+    /// only appear under Generations rules. Green on black by default.
+    pub fn set_cell_colors(&mut self, alive: &str, dead: &str, dying: &str) {
+        self.draw_style.alive = alive.to_string();
+        self.draw_style.dead = dead.to_string();
+        self.draw_style.dying = dying.to_string();
+    }
+
+    /// Draw grid lines between cells in the given CSS color, or leave them
+    /// out when `color` is `undefined`. Dark grey lines by default.
+    pub fn set_grid_lines(&mut self, color: Option<String>) {
+        self.draw_style.grid = color;
+    }
+
+    /// The width in pixels `draw` covers with cells of the given size.
+    pub fn canvas_width(&self, cell_size: f64) -> f64 {
+        self.canvas_extent(self.width, cell_size)
+    }
+
+    /// The height in pixels `draw` covers with cells of the given size.
+    pub fn canvas_height(&self, cell_size: f64) -> f64 {
+        self.canvas_extent(self.height, cell_size)
+    }
+
+    /// The cell under a point on `canvas`, given in client coordinates as
+    /// on a mouse or touch event, or `undefined` if the point is off the
+    /// board. `cell_size` and `gap`, the width of the grid lines, are in
+    /// the canvas's own pixels, as drawn; a canvas whose CSS size differs
+    /// from its pixel size, such as one sized for `devicePixelRatio`, is
+    /// scaled to match. The canvas must have no padding.
+    #[cfg(feature = "web")]
+    pub fn cell_at(
+        &self,
+        canvas: &web_sys::HtmlCanvasElement,
+        client_x: f64,
+        client_y: f64,
+        cell_size: f64,
+        gap: f64,
+    ) -> Option<CellCoord> {
+        let rect = canvas.get_bounding_client_rect();
+        // The client area leaves out the border.
+        let (css_width, css_height) = (canvas.client_width(), canvas.client_height());
+        if css_width <= 0 || css_height <= 0 {
+            return None;
+        }
+        let x = (client_x - rect.left() - f64::from(canvas.client_left()))
+            * f64::from(canvas.width())
+            / f64::from(css_width);
+        let y = (client_y - rect.top() - f64::from(canvas.client_top()))
+            * f64::from(canvas.height())
+            / f64::from(css_height);
+        self.cell_at_point(x, y, cell_size, gap)
+    }
+
+    /// The cell under the point `x`, `y` in canvas pixels from the board's
+    /// top-left corner, or `undefined` if the point is off the board. The
+    /// grid line before each cell counts as part of it, and the line after
+    /// the last row or column as part of that row or column.
+    pub fn cell_at_point(&self, x: f64, y: f64, cell_size: f64, gap: f64) -> Option<CellCoord> {
+        Some(CellCoord {
+            row: cell_along(y, self.height, cell_size, gap)?,
+            col: cell_along(x, self.width, cell_size, gap)?,
+        })
+    }
+
+    /// Draw the whole board with its top-left corner at the canvas origin,
+    /// covering `canvas_width` by `canvas_height` pixels.
+    ///
+    /// The board is filled with the dead color first, then each other
+    /// color is filled as a single path, so the number of canvas calls
+    /// doesn't depend on the number of cells.
+    #[cfg(feature = "web")]
+    pub fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d, cell_size: f64) {
+        let style = &self.draw_style;
+        let (board_width, board_height) =
+            (self.canvas_width(cell_size), self.canvas_height(cell_size));
+        ctx.set_fill_style_str(&style.dead);
+        ctx.fill_rect(0.0, 0.0, board_width, board_height);
+
+        if let Some(grid) = &style.grid {
+            // Lines centred on a pixel's middle cover just that pixel.
+            let pitch = cell_size + 1.0;
+            ctx.begin_path();
+            for col in 0..=self.width {
+                let x = f64::from(col) * pitch + 0.5;
+                ctx.move_to(x, 0.0);
+                ctx.line_to(x, board_height);
+            }
+            for row in 0..=self.height {
+                let y = f64::from(row) * pitch + 0.5;
+                ctx.move_to(0.0, y);
+                ctx.line_to(board_width, y);
+            }
+            ctx.set_stroke_style_str(grid);
+            ctx.set_line_width(1.0);
+            ctx.stroke();
+        }
+
+        self.fill_cells(ctx, cell_size, &style.alive, |_, state| state == ALIVE);
+        self.fill_cells(ctx, cell_size, &style.dying, |_, state| state > ALIVE);
+    }
+
+    /// Like `draw`, but only repaints the cells that changed since the last
+    /// call, which is much faster on large boards where few cells change
+    /// each generation. The first call, and any after the board is resized
+    /// or the cell size or colors change, paints the whole board.
+    ///
+    /// This assumes the canvas still shows what was last drawn; call
+    /// `invalidate_drawing` if it may not, such as after resizing it.
+    #[cfg(feature = "web")]
+    pub fn draw_delta(&mut self, ctx: &web_sys::CanvasRenderingContext2d, cell_size: f64) {
+        let current = self.drawn.as_ref().is_some_and(|drawn| {
+            (drawn.width, drawn.cells.len()) == (self.width, self.cells.len())
+                && drawn.cell_size == cell_size
+                && drawn.style == self.draw_style
+        });
+        let Some(mut drawn) = self.drawn.take().filter(|_| current) else {
+            self.draw(ctx, cell_size);
+            self.drawn = Some(Drawn {
+                width: self.width,
+                cell_size,
+                style: self.draw_style.clone(),
+                cells: self.cells.clone(),
+            });
+            return;
+        };
+
+        let style = &self.draw_style;
+        let was = &drawn.cells;
+        self.fill_cells(ctx, cell_size, &style.dead, |idx, state| {
+            state == DEAD && was[idx] != state
+        });
+        self.fill_cells(ctx, cell_size, &style.alive, |idx, state| {
+            state == ALIVE && was[idx] != state
+        });
+        self.fill_cells(ctx, cell_size, &style.dying, |idx, state| {
+            state > ALIVE && was[idx] != state
+        });
+        drawn.cells.copy_from_slice(&self.cells);
+        self.drawn = Some(drawn);
+    }
+
+    /// Make the next `draw_delta` paint the whole board.
+    #[cfg(feature = "web")]
+    pub fn invalidate_drawing(&mut self) {
+        self.drawn = None;
+    }
+}
+
+// The index of the cell `offset` pixels along a side of `cells` cells, each
+// `cell_size` pixels after a `gap` pixel line.
+fn cell_along(offset: f64, cells: u32, cell_size: f64, gap: f64) -> Option<u32> {
+    let pitch = cell_size + gap;
+    let extent = f64::from(cells) * pitch + gap;
+    if !(pitch > 0.0 && (0.0..extent).contains(&offset)) {
+        return None;
+    }
+    Some(((offset / pitch) as u32).min(cells - 1))
+}
+
+impl Universe {
+    fn canvas_extent(&self, cells: u32, cell_size: f64) -> f64 {
+        if self.draw_style.grid.is_some() {
+            f64::from(cells) * (cell_size + 1.0) + 1.0
+        } else {
+            f64::from(cells) * cell_size
+        }
+    }
+
+    // Fill every cell whose index and state match as one path, skipping the
+    // canvas calls entirely when none do.
+    #[cfg(feature = "web")]
+    fn fill_cells(
+        &self,
+        ctx: &web_sys::CanvasRenderingContext2d,
+        cell_size: f64,
+        color: &str,
+        matches: impl Fn(usize, u8) -> bool,
+    ) {
+        let (pitch, offset) = if self.draw_style.grid.is_some() {
+            (cell_size + 1.0, 1.0)
+        } else {
+            (cell_size, 0.0)
+        };
+        let mut any = false;
+        for (idx, &state) in self.cells.iter().enumerate() {
+            if !matches(idx, state) {
+                continue;
+            }
+            if !any {
+                ctx.begin_path();
+                any = true;
+            }
+            let row = (idx / self.width as usize) as f64;
+            let col = (idx % self.width as usize) as f64;
+            ctx.rect(
+                col * pitch + offset,
+                row * pitch + offset,
+                cell_size,
+                cell_size,
+            );
+        }
+        if any {
+            ctx.set_fill_style_str(color);
+            ctx.fill();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Drawing by dragging across the board.
+//
+// The first cell a drag touches decides what the whole drag paints: if it
+// was alive the drag clears cells, and otherwise it brings them to life.
+// Each cell is painted at most once per drag, and the cells between one
+// pointer position and the next are filled in, so fast drags leave no gaps.
+
+use std::collections::HashSet;
+
+use wasm_bindgen::prelude::*;
+
+use crate::shapes::line;
+use crate::Universe;
+
+// A drag in progress.
+#[derive(Clone, Debug)]
+pub struct DrawSession {
+    // The state every cell the drag touches is given.
+    state: u8,
+    // The size of the board when the drag began.
+    width: u32,
+    height: u32,
+    last: (u32, u32),
+    painted: HashSet<usize>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start a drag at the given cell, such as on `pointerdown`, painting
+    /// it as `toggle_cell` would and deciding what the rest of the drag
+    /// paints. Any drag already in progress ends. The whole drag is undone
+    /// in one step. Throws an error if the cell is outside the universe.
+    pub fn begin_draw(&mut self, row: u32, col: u32) -> Result<(), JsValue> {
+        let idx = self.checked_index(row, col)?;
+        self.remember();
+        let state = self.toggled_at(idx);
+        self.set_state(idx, state);
+        self.draw_session = Some(DrawSession {
+            state,
+            width: self.width,
+            height: self.height,
+            last: (row, col),
+            painted: HashSet::from([idx]),
+        });
+        Ok(())
+    }
+
+    /// Continue the drag to the given cell, such as on `pointermove`,
+    /// painting every cell on the line from the last one that this drag
+    /// hasn't painted yet. Does nothing when no drag is in progress, and
+    /// ends the drag if the universe has been resized since it began.
+    /// Throws an error if the cell is outside the universe.
+    pub fn draw_to(&mut self, row: u32, col: u32) -> Result<(), JsValue> {
+        self.checked_index(row, col)?;
+        let Some(mut session) = self.draw_session.take() else {
+            return Ok(());
+        };
+        if (session.width, session.height) != (self.width, self.height) {
+            return Ok(());
+        }
+
+        let (r0, c0) = session.last;
+        let start = (i64::from(r0), i64::from(c0));
+        line(start, (i64::from(row), i64::from(col)), |r, c| {
+            let idx = self.get_index(r as u32, c as u32);
+            if session.painted.insert(idx) {
+                self.set_state(idx, session.state);
+            }
+        });
+        session.last = (row, col);
+        self.draw_session = Some(session);
+        Ok(())
+    }
+
+    /// End the drag, such as on `pointerup` or `pointercancel`. Does
+    /// nothing when no drag is in progress.
+    pub fn end_draw(&mut self) {
+        self.draw_session = None;
+    }
+
+    /// Whether a drag begun with `begin_draw` is in progress.
+    pub fn is_drawing(&self) -> bool {
+        self.draw_session.is_some()
+    }
+}
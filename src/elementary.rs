@@ -0,0 +1,73 @@
+// Elementary one-dimensional cellular automata, drawn one generation per row.
+//
+// A Wolfram rule number from 0 to 255 gives the next state for each of the
+// eight possible (left, centre, right) neighbourhoods: bit `n` of the rule is
+// the new state when the neighbourhood, read as a 3-bit binary number, is `n`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::{Universe, ALIVE, DEAD};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to the elementary cellular automaton with the given Wolfram
+    /// rule, such as 30 or 110.
+    ///
+    /// Each row holds one generation. `tick` computes the row below the
+    /// bottom-most row with live cells, scrolling the universe up by a row
+    /// once the bottom row is reached; the left and right edges follow the
+    /// boundary set with `set_boundary`. Seed it by bringing cells to life
+    /// in the top row, and note that an empty universe stays empty.
+    pub fn set_elementary_rule(&mut self, rule: u8) {
+        self.apply_automaton(Automaton::Elementary(rule), 2);
+    }
+}
+
+impl Universe {
+    pub(crate) fn tick_elementary(&mut self, rule: u8) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        if width == 0 {
+            return;
+        }
+        let populated = self
+            .cells
+            .chunks(width)
+            .rposition(|row| row.contains(&ALIVE));
+        let Some(row) = populated else {
+            return;
+        };
+
+        let current = &self.cells[row * width..(row + 1) * width];
+        let alive = |col: i64| {
+            self.boundary
+                .neighbor(col, self.width)
+                .is_some_and(|col| current[col as usize] == ALIVE)
+        };
+        let generation: Vec<u8> = (0..width as i64)
+            .map(|col| {
+                let left = alive(col - 1);
+                let centre = alive(col);
+                let right = alive(col + 1);
+                let neighbourhood = (left as u8) << 2 | (centre as u8) << 1 | right as u8;
+                if rule & (1 << neighbourhood) != 0 {
+                    ALIVE
+                } else {
+                    DEAD
+                }
+            })
+            .collect();
+
+        let mut next = self.take_next();
+        let target = if row + 1 < height {
+            next.copy_from_slice(&self.cells);
+            row + 1
+        } else {
+            next[..(height - 1) * width].copy_from_slice(&self.cells[width..]);
+            height - 1
+        };
+        next[target * width..(target + 1) * width].copy_from_slice(&generation);
+        self.swap_in(next);
+    }
+}
@@ -0,0 +1,100 @@
+// DOM events reporting the simulation, for pages that would rather listen
+// for events than hand the universe a callback.
+//
+// Every few generations run by `tick`, `tick_n` or `tick_until_stable`, a
+// `life:tick` `CustomEvent` is dispatched on the chosen target, with the
+// numbers in its `detail`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+// The name of the event dispatched after ticking.
+const TICK_EVENT: &str = "life:tick";
+
+// Where and how often `tick` dispatches events.
+#[derive(Clone, Debug)]
+pub struct TickEvents {
+    target: web_sys::EventTarget,
+    every: u32,
+    // Generations run, and cells they changed, since the last event.
+    pending: u32,
+    changed: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Dispatch a `"life:tick"` `CustomEvent` on `target` after every
+    /// `every_n_ticks` generations run by `tick`, `tick_n` or
+    /// `tick_until_stable`, replacing any target already set. Its `detail`
+    /// holds the `generation`, the `population` and `changed`, the number of
+    /// cells born or died over the generations since the last event. Throws an
+    /// error if `every_n_ticks` is 0.
+    ///
+    /// A `tick_n` or `tick_until_stable` running past several intervals
+    /// dispatches one event when it finishes.
+    pub fn set_event_target(
+        &mut self,
+        target: &web_sys::EventTarget,
+        every_n_ticks: u32,
+    ) -> Result<(), JsValue> {
+        if every_n_ticks == 0 {
+            return Err(JsValue::from_str("every_n_ticks must be at least 1"));
+        }
+        self.tick_events = Some(TickEvents {
+            target: target.clone(),
+            every: every_n_ticks,
+            pending: 0,
+            changed: 0,
+        });
+        Ok(())
+    }
+
+    /// Stop dispatching events and let the target go.
+    pub fn clear_event_target(&mut self) {
+        self.tick_events = None;
+    }
+}
+
+impl Universe {
+    // Count the cells changed by the generation just run towards the next
+    // event.
+    pub(crate) fn count_tick_event(&mut self) {
+        let changes = self.changes;
+        if let Some(events) = &mut self.tick_events {
+            events.pending = events.pending.saturating_add(1);
+            events.changed = events
+                .changed
+                .saturating_add(changes.births + changes.deaths);
+        }
+    }
+
+    // Dispatch an event if enough generations have run since the last one.
+    pub(crate) fn dispatch_tick_event(&mut self) {
+        let population = match &self.tick_events {
+            Some(events) if events.pending >= events.every => self.population(),
+            _ => return,
+        };
+        let Some(events) = &mut self.tick_events else {
+            return;
+        };
+        let detail = js_sys::Object::new();
+        for (key, value) in [
+            ("generation", self.generation as f64),
+            ("population", f64::from(population)),
+            ("changed", f64::from(events.changed)),
+        ] {
+            let _ = js_sys::Reflect::set(&detail, &key.into(), &value.into());
+        }
+        events.pending = 0;
+        events.changed = 0;
+
+        let init = web_sys::CustomEventInit::new();
+        init.set_detail(&detail);
+        let dispatched = web_sys::CustomEvent::new_with_event_init_dict(TICK_EVENT, &init)
+            .and_then(|event| events.target.dispatch_event(&event));
+        if let Err(error) = dispatched {
+            web_sys::console::error_2(&"Could not dispatch life:tick:".into(), &error);
+        }
+    }
+}
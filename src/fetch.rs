@@ -0,0 +1,90 @@
+// Downloading patterns over the network.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::pattern::Pattern;
+use crate::{life106, plaintext, rle, PlacementMode, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Download an RLE, plaintext `.cells` or Life 1.06 file and place it
+    /// with its top-left corner at the given position.
+    ///
+    /// The format is detected from the file contents. The returned promise
+    /// rejects on network errors, non-2xx responses and parse failures, in
+    /// which case the universe is left unmodified; otherwise the generation
+    /// counter restarts at 0. The universe stays borrowed until the promise
+    /// settles, so await it before calling other methods.
+    pub async fn load_pattern_url(
+        &mut self,
+        url: String,
+        row: u32,
+        col: u32,
+    ) -> Result<(), JsValue> {
+        let text = fetch_text(&url).await?;
+        let pattern = parse_any(&text).map_err(|e| {
+            JsValue::from_str(&format!("Could not parse pattern from {}: {}", url, e))
+        })?;
+        self.remember();
+        self.place(&pattern, row, col, PlacementMode::Replace);
+        self.generation = 0;
+        Ok(())
+    }
+}
+
+async fn fetch_text(url: &str) -> Result<String, JsValue> {
+    let window =
+        web_sys::window().ok_or_else(|| JsValue::from_str("No window available to fetch from"))?;
+
+    let response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to fetch {}: {}", url, describe(&e))))?;
+    let response: web_sys::Response = response.dyn_into()?;
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!(
+            "Fetching {} failed with HTTP {} {}",
+            url,
+            response.status(),
+            response.status_text()
+        )));
+    }
+
+    let text = JsFuture::from(response.text()?)
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to read {}: {}", url, describe(&e))))?;
+    text.as_string()
+        .ok_or_else(|| JsValue::from_str(&format!("Response from {} is not text", url)))
+}
+
+// Detect the pattern format from the file contents and parse it.
+fn parse_any(text: &str) -> Result<Pattern, String> {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    if first == "#Life 1.06" {
+        return life106::parse(text);
+    }
+
+    let is_rle = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| line.starts_with('x') && line.contains('='));
+    if is_rle {
+        rle::parse(text)
+    } else {
+        plaintext::parse(text)
+    }
+}
+
+// Render a JavaScript error value as a message.
+pub(crate) fn describe(error: &JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| error.as_string())
+        .unwrap_or_else(|| format!("{:?}", error))
+}
@@ -0,0 +1,230 @@
+// Playing a universe on a canvas from Rust, with `requestAnimationFrame`.
+//
+// The frame callback only holds a weak reference to the loop's state, so
+// the state and the callback it stores are freed together when the loop
+// is. The flags and settings are `Cell`s so that JavaScript called from
+// inside a tick, such as a tick callback, can pause the loop while the
+// universe is borrowed.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::Universe;
+
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+struct Shared {
+    universe: RefCell<Universe>,
+    ctx: web_sys::CanvasRenderingContext2d,
+    cell_size: f64,
+    playing: Cell<bool>,
+    ticks_per_frame: Cell<u32>,
+    // The least time between frames that tick, in milliseconds; 0 for no
+    // cap.
+    min_interval: Cell<f64>,
+    // When the last frame that ticked was due.
+    last_frame: Cell<Option<f64>>,
+    // The pending animation frame, if one is requested.
+    frame: Cell<Option<i32>>,
+    callback: RefCell<Option<FrameCallback>>,
+}
+
+/// Plays a universe on a canvas, ticking and drawing it once per animation
+/// frame until paused.
+#[wasm_bindgen]
+pub struct GameLoop {
+    shared: Rc<Shared>,
+}
+
+#[wasm_bindgen]
+impl GameLoop {
+    /// Take over `universe` to play it on `canvas` with cells `cell_size`
+    /// pixels square, sizing the canvas to fit and drawing the board. The
+    /// loop starts paused. Throws an error if the canvas has no 2D context.
+    pub fn new(
+        universe: Universe,
+        canvas: web_sys::HtmlCanvasElement,
+        cell_size: f64,
+    ) -> Result<GameLoop, JsValue> {
+        let ctx = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("Canvas has no 2D context"))?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+        canvas.set_width(universe.canvas_width(cell_size) as u32);
+        canvas.set_height(universe.canvas_height(cell_size) as u32);
+
+        let shared = Rc::new(Shared {
+            universe: RefCell::new(universe),
+            ctx,
+            cell_size,
+            playing: Cell::new(false),
+            ticks_per_frame: Cell::new(1),
+            min_interval: Cell::new(0.0),
+            last_frame: Cell::new(None),
+            frame: Cell::new(None),
+            callback: RefCell::new(None),
+        });
+        shared
+            .universe
+            .borrow_mut()
+            .draw_delta(&shared.ctx, shared.cell_size);
+        Ok(GameLoop { shared })
+    }
+
+    /// Start playing from the next animation frame. Does nothing if the
+    /// loop is already playing.
+    pub fn play(&mut self) -> Result<(), JsValue> {
+        if self.shared.playing.get() {
+            return Ok(());
+        }
+        self.shared.playing.set(true);
+        self.shared.last_frame.set(None);
+        if self.shared.callback.borrow().is_none() {
+            let weak = Rc::downgrade(&self.shared);
+            let callback = FrameCallback::new(move |time| run_frame(&weak, time));
+            *self.shared.callback.borrow_mut() = Some(callback);
+        }
+        request_frame(&self.shared).inspect_err(|_| self.shared.playing.set(false))
+    }
+
+    /// Stop playing after the current frame, leaving the board as it is.
+    pub fn pause(&mut self) {
+        self.shared.playing.set(false);
+        cancel_frame(&self.shared);
+    }
+
+    /// Pause if playing, or play if paused, returning whether the loop is
+    /// now playing.
+    pub fn toggle(&mut self) -> Result<bool, JsValue> {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.play()?;
+        }
+        Ok(self.is_playing())
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.shared.playing.get()
+    }
+
+    /// Run `n` generations each frame before drawing, 1 by default. Throws
+    /// an error if `n` is 0.
+    pub fn set_ticks_per_frame(&mut self, n: u32) -> Result<(), JsValue> {
+        if n == 0 {
+            return Err(JsValue::from_str("Ticks per frame must be at least 1"));
+        }
+        self.shared.ticks_per_frame.set(n);
+        Ok(())
+    }
+
+    /// Tick and draw at most `fps` times a second, skipping animation
+    /// frames in between, or on every frame when `fps` is 0, the default.
+    /// Throws an error if `fps` is negative or not a number.
+    pub fn set_fps_cap(&mut self, fps: f32) -> Result<(), JsValue> {
+        if fps.is_nan() || fps < 0.0 {
+            return Err(JsValue::from_str(&format!(
+                "FPS cap must be 0 or more, not {}",
+                fps
+            )));
+        }
+        let interval = if fps == 0.0 {
+            0.0
+        } else {
+            1000.0 / f64::from(fps)
+        };
+        self.shared.min_interval.set(interval);
+        self.shared.last_frame.set(None);
+        Ok(())
+    }
+
+    /// Pause, cancelling any pending animation frame, and release the
+    /// frame callback. `play` starts the loop again. Freeing the loop does
+    /// the same.
+    pub fn stop(&mut self) {
+        self.pause();
+        self.shared.callback.borrow_mut().take();
+    }
+
+    /// Stop the loop and hand back its universe, freeing the loop. Throws
+    /// an error if called while the universe is ticking, such as from a
+    /// tick callback.
+    pub fn into_universe(mut self) -> Result<Universe, JsValue> {
+        self.stop();
+        let shared = self.shared.clone();
+        drop(self);
+        Rc::try_unwrap(shared)
+            .map(|shared| shared.universe.into_inner())
+            .map_err(|_| JsValue::from_str("Can't release the universe while it is ticking"))
+    }
+}
+
+impl Drop for GameLoop {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Tick and draw if a frame is due, then ask for the next one.
+fn run_frame(shared: &Weak<Shared>, time: f64) {
+    let Some(shared) = shared.upgrade() else {
+        return;
+    };
+    shared.frame.set(None);
+    if !shared.playing.get() {
+        return;
+    }
+
+    let interval = shared.min_interval.get();
+    let due = match shared.last_frame.get() {
+        Some(last) if interval > 0.0 => {
+            let elapsed = time - last;
+            // Keep to the cap's schedule rather than the frames', so a cap
+            // that doesn't divide the refresh rate still averages out.
+            (elapsed >= interval).then(|| time - elapsed % interval)
+        }
+        _ => Some(time),
+    };
+    if let Some(start) = due {
+        shared.last_frame.set(Some(start));
+        if let Ok(mut universe) = shared.universe.try_borrow_mut() {
+            for _ in 0..shared.ticks_per_frame.get() {
+                universe.tick();
+            }
+            universe.draw_delta(&shared.ctx, shared.cell_size);
+        }
+    }
+
+    // Ticking may have paused the loop.
+    if shared.playing.get() {
+        if let Err(error) = request_frame(&shared) {
+            web_sys::console::error_2(&"Game loop stopped:".into(), &error);
+            shared.playing.set(false);
+        }
+    }
+}
+
+fn request_frame(shared: &Shared) -> Result<(), JsValue> {
+    if shared.frame.get().is_some() {
+        return Ok(());
+    }
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window available"))?;
+    let callback = shared.callback.borrow();
+    let Some(callback) = callback.as_ref() else {
+        return Ok(());
+    };
+    let id = window.request_animation_frame(callback.as_ref().unchecked_ref())?;
+    shared.frame.set(Some(id));
+    Ok(())
+}
+
+fn cancel_frame(shared: &Shared) {
+    if let Some(id) = shared.frame.take() {
+        if let Some(window) = web_sys::window() {
+            let _ = window.cancel_animation_frame(id);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+// Hashlife, for jumping a pattern far into the future.
+//
+// The plane is a quadtree whose nodes are shared: every distinct square of
+// cells is stored once, so repeated structure costs nothing extra. For a
+// node of side 2^k the centre square of side 2^(k-1) can be computed
+// 2^(k-2) generations ahead from the node alone, and since nodes are shared
+// that result is remembered and reused wherever the same square appears.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::neighborhood::Neighborhood;
+use crate::rules::Rule;
+use crate::{Universe, ALIVE, DEAD};
+
+// The most nodes a single jump may create before giving up.
+const MAX_NODES: usize = 1 << 21;
+
+// The leaves: single dead and live cells.
+const DEAD_LEAF: u32 = 0;
+const ALIVE_LEAF: u32 = 1;
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    // The quadrants in the order north-west, north-east, south-west,
+    // south-east; unused for leaves.
+    children: [u32; 4],
+    level: u8,
+    population: u64,
+}
+
+struct Hashlife {
+    rule: Rule,
+    neighborhood: Neighborhood,
+    nodes: Vec<Node>,
+    index: HashMap<[u32; 4], u32>,
+    // The centre of a node advanced by 2^j generations, keyed by node and j.
+    results: HashMap<(u32, u8), u32>,
+    // The empty node of each level.
+    empty: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Advance `generations` generations at once using Hashlife, which can
+    /// run a Gosper gun a million generations in moments.
+    ///
+    /// The pattern evolves as if the universe had no edges, and only the
+    /// part that ends up inside the grid is kept, so this matches `tick`
+    /// only while nothing reaches the edges. It supports two-state
+    /// Life-like rules without `B0`. Throws an error for other rules, or if
+    /// the pattern needs more memory than the node limit allows, in which
+    /// case the universe is left unchanged.
+    pub fn tick_hashlife(&mut self, generations: u64) -> Result<(), JsValue> {
+        if self.automaton != Automaton::Life || self.custom_rule.is_some() {
+            return Err(JsValue::from_str(
+                "Hashlife only supports Life-like rules, not custom rules or other automata",
+            ));
+        }
+        if self.rule.states != 2 {
+            return Err(JsValue::from_str(&format!(
+                "Hashlife only supports two-state rules, not {}",
+                self.rule
+            )));
+        }
+        if self.rule.birth & 1 != 0 {
+            return Err(JsValue::from_str(&format!(
+                "Hashlife can't run B0 rules such as {}",
+                self.rule
+            )));
+        }
+
+        let mut life = Hashlife::new(self.rule, self.neighborhood);
+        let cells = life
+            .advance(self, generations)
+            .map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
+        self.replace_cells(cells);
+        self.generation = self.generation.saturating_add(generations);
+        Ok(())
+    }
+}
+
+impl Hashlife {
+    fn new(rule: Rule, neighborhood: Neighborhood) -> Hashlife {
+        let leaf = |population| Node {
+            children: [0; 4],
+            level: 0,
+            population,
+        };
+        Hashlife {
+            rule,
+            neighborhood,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD_LEAF],
+        }
+    }
+
+    // Run the universe's cells `generations` generations ahead, returning
+    // the new cells of its grid.
+    fn advance(&mut self, universe: &Universe, generations: u64) -> Result<Vec<u8>, String> {
+        let side = universe.width.max(universe.height).next_power_of_two();
+        // Expanding and stepping need at least a 4x4 square.
+        let level = (side.trailing_zeros() as u8).max(2);
+        let mut root = self.build(universe, level, 0, 0)?;
+        // The grid position of the root's top-left corner.
+        let mut origin = (0i64, 0i64);
+
+        for j in (0..64).filter(|j| generations >> j & 1 == 1) {
+            // Pad the pattern until it sits in the middle quarter of a node
+            // large enough to step 2^j generations, so nothing it grows into
+            // falls outside the result.
+            while self.nodes[root as usize].level < j + 2 || !self.is_padded(root)? {
+                (root, origin) = self.expand(root, origin)?;
+            }
+            (root, origin) = self.expand(root, origin)?;
+
+            let offset = 1i64 << (self.nodes[root as usize].level - 2);
+            root = self.step(root, j)?;
+            origin = (origin.0 + offset, origin.1 + offset);
+        }
+
+        let mut cells = vec![DEAD; universe.cells.len()];
+        self.write(root, origin, universe, &mut cells);
+        Ok(cells)
+    }
+
+    // The node covering the square of side 2^level with its top-left corner
+    // at the given grid position.
+    fn build(&mut self, universe: &Universe, level: u8, row: u32, col: u32) -> Result<u32, String> {
+        if row >= universe.height || col >= universe.width {
+            return self.empty(level);
+        }
+        if level == 0 {
+            let alive = universe.cells[universe.get_index(row, col)] == ALIVE;
+            return Ok(if alive { ALIVE_LEAF } else { DEAD_LEAF });
+        }
+        let half = 1 << (level - 1);
+        let nw = self.build(universe, level - 1, row, col)?;
+        let ne = self.build(universe, level - 1, row, col + half)?;
+        let sw = self.build(universe, level - 1, row + half, col)?;
+        let se = self.build(universe, level - 1, row + half, col + half)?;
+        self.join([nw, ne, sw, se])
+    }
+
+    // Copy the live cells of a node whose top-left corner is at `origin`
+    // into the universe's grid, leaving out any beyond its edges.
+    fn write(&self, node: u32, origin: (i64, i64), universe: &Universe, cells: &mut [u8]) {
+        let Node {
+            children,
+            level,
+            population,
+        } = self.nodes[node as usize];
+        let side = 1i64 << level;
+        let (row, col) = origin;
+        if population == 0
+            || row >= i64::from(universe.height)
+            || col >= i64::from(universe.width)
+            || row + side <= 0
+            || col + side <= 0
+        {
+            return;
+        }
+        if level == 0 {
+            cells[universe.get_index(row as u32, col as u32)] = ALIVE;
+            return;
+        }
+        let half = side / 2;
+        let corners = [(0, 0), (0, half), (half, 0), (half, half)];
+        for (child, (dr, dc)) in children.into_iter().zip(corners) {
+            self.write(child, (row + dr, col + dc), universe, cells);
+        }
+    }
+
+    // The node with the given quadrants, shared with any identical node.
+    fn join(&mut self, children: [u32; 4]) -> Result<u32, String> {
+        if let Some(&node) = self.index.get(&children) {
+            return Ok(node);
+        }
+        if self.nodes.len() >= MAX_NODES {
+            return Err(format!(
+                "Hashlife ran out of memory after {} nodes",
+                MAX_NODES
+            ));
+        }
+        let level = self.nodes[children[0] as usize].level + 1;
+        let population = children
+            .iter()
+            .map(|&child| self.nodes[child as usize].population)
+            .sum();
+        let node = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            children,
+            level,
+            population,
+        });
+        self.index.insert(children, node);
+        Ok(node)
+    }
+
+    fn empty(&mut self, level: u8) -> Result<u32, String> {
+        while self.empty.len() <= usize::from(level) {
+            let smaller = *self.empty.last().unwrap();
+            let node = self.join([smaller; 4])?;
+            self.empty.push(node);
+        }
+        Ok(self.empty[usize::from(level)])
+    }
+
+    fn children(&self, node: u32) -> [u32; 4] {
+        self.nodes[node as usize].children
+    }
+
+    // Whether every live cell of a node lies in its central square.
+    fn is_padded(&mut self, node: u32) -> Result<bool, String> {
+        let centre = self.centre(node)?;
+        Ok(self.nodes[centre as usize].population == self.nodes[node as usize].population)
+    }
+
+    // The central square of a node, half its side.
+    fn centre(&mut self, node: u32) -> Result<u32, String> {
+        let [nw, ne, sw, se] = self.children(node);
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    // A node twice the side with `node` in the middle, and its origin.
+    fn expand(&mut self, node: u32, origin: (i64, i64)) -> Result<(u32, (i64, i64)), String> {
+        let level = self.nodes[node as usize].level;
+        let border = self.empty(level - 1)?;
+        let [nw, ne, sw, se] = self.children(node);
+        let expanded = [
+            self.join([border, border, border, nw])?,
+            self.join([border, border, ne, border])?,
+            self.join([border, sw, border, border])?,
+            self.join([se, border, border, border])?,
+        ];
+        let shift = 1i64 << (level - 1);
+        Ok((self.join(expanded)?, (origin.0 - shift, origin.1 - shift)))
+    }
+
+    // The central square of a node of level k advanced by 2^j generations,
+    // where j is at most k - 2.
+    fn step(&mut self, node: u32, j: u8) -> Result<u32, String> {
+        if let Some(&result) = self.results.get(&(node, j)) {
+            return Ok(result);
+        }
+        let level = self.nodes[node as usize].level;
+        let result = if self.nodes[node as usize].population == 0 {
+            self.empty(level - 1)?
+        } else if level == 2 {
+            self.step_base(node)?
+        } else {
+            // Nine overlapping squares of half the side, row by row.
+            let [nw, ne, sw, se] = self.children(node);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+            let squares = [
+                nw,
+                self.join([nw_ne, ne_nw, nw_se, ne_sw])?,
+                ne,
+                self.join([nw_sw, nw_se, sw_nw, sw_ne])?,
+                self.join([nw_se, ne_sw, sw_ne, se_nw])?,
+                self.join([ne_sw, ne_se, se_nw, se_ne])?,
+                sw,
+                self.join([sw_ne, se_nw, sw_se, se_sw])?,
+                se,
+            ];
+
+            // At full speed each half of the jump is taken by a recursive
+            // step; otherwise the first half just takes the centres.
+            let full = j + 2 == level;
+            let mut parts = [0; 9];
+            for (part, square) in parts.iter_mut().zip(squares) {
+                *part = if full {
+                    self.step(square, j - 1)?
+                } else {
+                    self.centre(square)?
+                };
+            }
+            let second = if full { j - 1 } else { j };
+            let [a, b, c, d, e, f, g, h, i] = parts;
+            let mut quadrants = [0; 4];
+            for (quadrant, children) in
+                quadrants
+                    .iter_mut()
+                    .zip([[a, b, d, e], [b, c, e, f], [d, e, g, h], [e, f, h, i]])
+            {
+                let joined = self.join(children)?;
+                *quadrant = self.step(joined, second)?;
+            }
+            self.join(quadrants)?
+        };
+        self.results.insert((node, j), result);
+        Ok(result)
+    }
+
+    // The central 2x2 cells of a 4x4 node after one generation.
+    fn step_base(&mut self, node: u32) -> Result<u32, String> {
+        let mut grid = [[DEAD; 4]; 4];
+        for (quadrant, child) in self.children(node).into_iter().enumerate() {
+            for (cell, leaf) in self.children(child).into_iter().enumerate() {
+                let row = quadrant / 2 * 2 + cell / 2;
+                let col = quadrant % 2 * 2 + cell % 2;
+                grid[row][col] = if leaf == ALIVE_LEAF { ALIVE } else { DEAD };
+            }
+        }
+
+        let mut centre = [DEAD_LEAF; 4];
+        for (cell, leaf) in centre.iter_mut().enumerate() {
+            let (row, col) = (1 + cell / 2, 1 + cell % 2);
+            let live_neighbors = self
+                .neighborhood
+                .offsets()
+                .iter()
+                .filter(|&&(dr, dc)| {
+                    grid[(row as i32 + dr) as usize][(col as i32 + dc) as usize] == ALIVE
+                })
+                .count() as u8;
+            if self.rule.next(grid[row][col], live_neighbors) == ALIVE {
+                *leaf = ALIVE_LEAF;
+            }
+        }
+        self.join(centre)
+    }
+}
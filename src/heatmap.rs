@@ -0,0 +1,73 @@
+// Counting how often each cell changes state, to show where a pattern is
+// most active.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop counting, for every cell, how many generations changed
+    /// its state. Off by default; turning it off frees the counts, and
+    /// turning it on starts them from zero. Counts stop at 2^32 - 1 rather
+    /// than wrapping.
+    pub fn enable_heatmap(&mut self, enabled: bool) {
+        self.heatmap = enabled.then(|| vec![0; self.cells.len()]);
+    }
+
+    /// Pointer to the counts, one `u32` per cell in row-major order, or null
+    /// while the heatmap is off. They start over from zero whenever the
+    /// universe changes size.
+    pub fn heatmap(&mut self) -> *const u32 {
+        self.fit_heatmap();
+        self.heatmap
+            .as_ref()
+            .map_or(std::ptr::null(), |heat| heat.as_ptr())
+    }
+
+    /// The number of counts behind `heatmap`: one per cell, or 0 while the
+    /// heatmap is off.
+    pub fn heatmap_len(&self) -> u32 {
+        self.heatmap.as_ref().map_or(0, |_| self.cells.len() as u32)
+    }
+
+    /// The highest count, for scaling the others; 0 while the heatmap is
+    /// off.
+    pub fn heatmap_max(&self) -> u32 {
+        self.heatmap
+            .as_ref()
+            .filter(|heat| heat.len() == self.cells.len())
+            .and_then(|heat| heat.iter().copied().max())
+            .unwrap_or(0)
+    }
+
+    /// Set every count back to zero.
+    pub fn reset_heatmap(&mut self) {
+        if let Some(heat) = &mut self.heatmap {
+            heat.clear();
+            heat.resize(self.cells.len(), 0);
+        }
+    }
+}
+
+impl Universe {
+    // Start the counts over if the universe has changed size since.
+    pub(crate) fn fit_heatmap(&mut self) {
+        if self
+            .heatmap
+            .as_ref()
+            .is_some_and(|heat| heat.len() != self.cells.len())
+        {
+            self.reset_heatmap();
+        }
+    }
+}
+
+// Count the cells that differ between two generations of the same board.
+pub fn record(heat: &mut [u32], before: &[u8], after: &[u8]) {
+    for ((count, &was), &now) in heat.iter_mut().zip(before).zip(after) {
+        if was != now {
+            *count = count.saturating_add(1);
+        }
+    }
+}
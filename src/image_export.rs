@@ -0,0 +1,116 @@
+// Encoding the board as image files, with the `image-export` feature.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, ALIVE, DEAD};
+
+// The most pixels a GIF may have over all its frames.
+const MAX_GIF_PIXELS: u64 = 1 << 28;
+
+// Dead cells are black, live cells green and dying cells dark green, as on
+// the canvas.
+const GIF_PALETTE: [u8; 9] = [0x00, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x66, 0x00];
+
+#[wasm_bindgen]
+impl Universe {
+    /// The board as a PNG file, drawn as by `render_rgba`: each cell a
+    /// `cell_size` pixel square in the given 0xRRGGBBAA colors. Throws an
+    /// error if `cell_size` is 0 or the image would have more than 2^26
+    /// pixels.
+    pub fn to_png(
+        &self,
+        cell_size: u32,
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<Vec<u8>, JsValue> {
+        let pixels = self.render_rgba(cell_size, alive_color, dead_color)?;
+        let mut file = Vec::new();
+        let mut encoder =
+            png::Encoder::new(&mut file, self.width * cell_size, self.height * cell_size);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|e| JsValue::from_str(&format!("Could not encode PNG: {}", e)))?;
+        Ok(file)
+    }
+
+    /// An animated GIF of the board followed by the next `generations`
+    /// generations, each cell a `cell_size` pixel square, showing each
+    /// frame for `frame_delay_ms`. The generations are run on a copy, so
+    /// this universe is left as it was, and the board doesn't grow even
+    /// with `set_auto_grow` on. Delays are rounded down to 10ms steps.
+    ///
+    /// Throws an error if `cell_size` is 0, a frame would be more than
+    /// 65535 pixels across, or all the frames together more than 2^28
+    /// pixels.
+    pub fn record_gif(
+        &self,
+        generations: u32,
+        cell_size: u32,
+        frame_delay_ms: u16,
+    ) -> Result<Vec<u8>, JsValue> {
+        if cell_size == 0 {
+            return Err(JsValue::from_str("Cell size must be at least 1"));
+        }
+        let side = |cells: u32| u16::try_from(u64::from(cells) * u64::from(cell_size)).ok();
+        let (Some(width), Some(height)) = (side(self.width), side(self.height)) else {
+            return Err(JsValue::from_str(&format!(
+                "A {}x{} GIF with {} pixel cells is too large",
+                self.width, self.height, cell_size
+            )));
+        };
+        let frames = u64::from(generations) + 1;
+        if frames * u64::from(width) * u64::from(height) > MAX_GIF_PIXELS {
+            return Err(JsValue::from_str(&format!(
+                "{} frames of {}x{} pixels is too much for one GIF",
+                frames, width, height
+            )));
+        }
+
+        let encoding_error =
+            |e: gif::EncodingError| JsValue::from_str(&format!("Could not encode GIF: {}", e));
+        let mut encoder =
+            gif::Encoder::new(Vec::new(), width, height, &GIF_PALETTE).map_err(encoding_error)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(encoding_error)?;
+
+        let mut universe = self.scratch_copy();
+        let mut pixels = vec![0; usize::from(width) * usize::from(height)];
+        for frame in 0..frames {
+            if frame > 0 {
+                universe.tick();
+            }
+            universe.index_pixels(cell_size as usize, &mut pixels);
+            let mut frame = gif::Frame::from_indexed_pixels(width, height, &pixels[..], None);
+            frame.delay = frame_delay_ms / 10;
+            encoder.write_frame(&frame).map_err(encoding_error)?;
+        }
+        encoder.into_inner().map_err(encoding_error)
+    }
+}
+
+impl Universe {
+    // Fill `pixels` with the palette index of every pixel of the board.
+    fn index_pixels(&self, cell_size: usize, pixels: &mut [u8]) {
+        let row_pixels = self.width as usize * cell_size;
+        for (band, row) in pixels
+            .chunks_exact_mut(row_pixels * cell_size)
+            .zip(self.cells.chunks_exact(self.width as usize))
+        {
+            let (first, rest) = band.split_at_mut(row_pixels);
+            for (cell, &state) in first.chunks_exact_mut(cell_size).zip(row) {
+                cell.fill(match state {
+                    DEAD => 0,
+                    ALIVE => 1,
+                    _ => 2,
+                });
+            }
+            for copy in rest.chunks_exact_mut(row_pixels) {
+                copy.copy_from_slice(first);
+            }
+        }
+    }
+}
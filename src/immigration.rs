@@ -0,0 +1,100 @@
+// Immigration, a two-player variant of Conway's Game of Life.
+//
+// Live cells belong to player one or player two. Births and deaths follow
+// B3/S23, survivors keep their owner and a newborn cell takes the owner of
+// the majority of its three parents.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::{Universe, DEAD};
+
+pub const PLAYER_ONE: u8 = 1;
+pub const PLAYER_TWO: u8 = 2;
+pub const STATES: u8 = 3;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Toggle a cell for `player` 1 or 2: a cell the player owns dies, and
+    /// any other cell comes alive for the player.
+    ///
+    /// Throws an error unless the Immigration automaton is selected.
+    pub fn toggle_cell_for_player(
+        &mut self,
+        row: u32,
+        column: u32,
+        player: u8,
+    ) -> Result<(), JsValue> {
+        self.require_immigration()?;
+        if player != PLAYER_ONE && player != PLAYER_TWO {
+            return Err(JsValue::from_str(&format!(
+                "Invalid player {}; expected 1 or 2",
+                player
+            )));
+        }
+        let idx = self.get_index(row, column);
+        let state = if self.state(idx) == player {
+            DEAD
+        } else {
+            player
+        };
+        self.set_state(idx, state);
+        Ok(())
+    }
+
+    /// Fill the universe at random like `randomize`, giving each live cell
+    /// to either player with equal chance.
+    ///
+    /// Throws an error unless the Immigration automaton is selected.
+    pub fn randomize_two_player(&mut self) -> Result<(), JsValue> {
+        self.require_immigration()?;
+        self.remember();
+        for idx in 0..self.cells.len() {
+            let roll = self.rng.next_f64();
+            let state = if roll < 0.15 {
+                PLAYER_ONE
+            } else if roll < 0.3 {
+                PLAYER_TWO
+            } else {
+                DEAD
+            };
+            self.set_state(idx, state);
+        }
+        Ok(())
+    }
+
+    /// The number of live cells each player owns, as `[player1, player2]`.
+    pub fn score(&self) -> js_sys::Uint32Array {
+        let count = |player| self.cells.iter().filter(|&&cell| cell == player).count() as u32;
+        js_sys::Uint32Array::from(&[count(PLAYER_ONE), count(PLAYER_TWO)][..])
+    }
+}
+
+impl Universe {
+    fn require_immigration(&self) -> Result<(), JsValue> {
+        if self.automaton != Automaton::Immigration {
+            return Err(JsValue::from_str(
+                "Two-player cells need the immigration automaton; call set_automaton(\"immigration\") first",
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn immigration_next(&self, row: u32, column: u32) -> u8 {
+        // Neighbours owned by nobody, player one and player two.
+        let mut owners = [0u8; 3];
+        for idx in self.neighbor_indices(row, column) {
+            owners[self.cells[idx] as usize] += 1;
+        }
+
+        let cell = self.cells[self.get_index(row, column)];
+        let live = owners[1] + owners[2];
+        match cell {
+            DEAD if live == 3 && owners[2] > owners[1] => PLAYER_TWO,
+            DEAD if live == 3 => PLAYER_ONE,
+            DEAD => DEAD,
+            _ if live == 2 || live == 3 => cell,
+            _ => DEAD,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Serializing the universe with serde, and JSON export built on it.
+//
+// A universe serializes as its size, generation, rule and cells:
+//
+//   {"width": 64, "height": 64, "generation": 12, "rule": "B3/S23",
+//    "packed": true, "cells": "AAAAQ..."}
+//
+// The cells are base64, packed a bit per cell as for undo when every cell
+// is dead or alive and a byte per cell otherwise. Everything else about the
+// universe, such as its history or ants, is left out.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::undo::SavedBoard;
+use crate::{base64, check_dimensions, Universe};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Universe", deny_unknown_fields)]
+struct UniverseData {
+    width: u32,
+    height: u32,
+    generation: u64,
+    rule: String,
+    packed: bool,
+    cells: String,
+}
+
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let board = self.save_board();
+        UniverseData {
+            width: board.width,
+            height: board.height,
+            generation: board.generation,
+            rule: self.rule.to_string(),
+            packed: board.packed,
+            cells: base64::encode(&board.data),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        let data = UniverseData::deserialize(deserializer)?;
+        let (rule, board) = parse(data).map_err(D::Error::custom)?;
+        let mut universe = Universe::new();
+        universe.apply_rule(rule);
+        universe.load_board(&board);
+        Ok(universe)
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The size, generation, rule and cells as JSON, which `from_json`
+    /// reads back.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a universe always serializes")
+    }
+
+    /// A universe from JSON written by `to_json`. Throws an error if the
+    /// JSON is malformed or describes an impossible board.
+    pub fn from_json(json: &str) -> Result<Universe, JsValue> {
+        serde_json::from_str(json).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+fn parse(data: UniverseData) -> Result<(Rule, SavedBoard), String> {
+    let size = check_dimensions(data.width, data.height)?;
+    let rule = Rule::parse(&data.rule)?;
+    let cells = base64::decode(&data.cells)?;
+
+    let expected = if data.packed { size.div_ceil(8) } else { size };
+    if cells.len() != expected {
+        return Err(format!(
+            "Expected {} bytes of cells for a {}x{} universe, found {}",
+            expected,
+            data.width,
+            data.height,
+            cells.len()
+        ));
+    }
+    if !data.packed {
+        if let Some(state) = cells.iter().find(|&&state| state >= rule.states) {
+            return Err(format!(
+                "Cell state {} is out of range for rule {}, which has {} states",
+                state, rule, rule.states
+            ));
+        }
+    }
+
+    let board = SavedBoard {
+        width: data.width,
+        height: data.height,
+        generation: data.generation,
+        packed: data.packed,
+        data: cells,
+    };
+    Ok((rule, board))
+}
@@ -1,19 +1,127 @@
 use wasm_bindgen::prelude::*;
-use web_sys::console;
 
-// Macro for logging to browser console
+mod ages;
+mod ants;
+mod automaton;
+mod base64;
+mod bitset;
+mod boundary;
+#[cfg(feature = "web")]
+mod callbacks;
+mod checkpoint;
+mod clipboard;
+mod components;
+mod counts;
+mod custom_rule;
+mod delta;
+mod draw;
+mod draw_session;
+mod elementary;
+#[cfg(feature = "web")]
+mod events;
+#[cfg(feature = "web")]
+mod fetch;
+#[cfg(feature = "web")]
+mod game_loop;
+mod hashlife;
+mod heatmap;
+#[cfg(feature = "image-export")]
+mod image_export;
+mod immigration;
+#[cfg(feature = "serde")]
+mod json;
+mod life106;
+mod ltl;
+mod map_rule;
+mod mutation;
+mod neighborhood;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod pattern;
+mod period;
+mod plaintext;
+mod platform;
+mod population;
+mod presets;
+mod profiling;
+mod random;
+mod render;
+mod resize;
+mod rewind;
+mod rgba;
+mod rle;
+mod rng;
+mod rules;
+mod scene;
+mod search;
+mod selection;
+mod shapes;
+mod share;
+mod snapshot;
+mod soup;
+mod sparse;
+#[cfg(feature = "web")]
+mod storage;
+mod svg;
+#[cfg(test)]
+mod testing;
+mod text;
+mod tiles;
+mod trail;
+mod transform;
+mod undo;
+mod wireworld;
+
+use ants::Ant;
+use automaton::Automaton;
+use bitset::LiveBits;
+use boundary::Boundary;
+use checkpoint::Checkpoints;
+use counts::NeighborCounts;
+use custom_rule::CustomRule;
+use draw::DrawStyle;
+use neighborhood::Neighborhood;
+use pattern::{Category, Pattern};
+use period::History;
+use population::{Changes, PopulationHistory};
+use profiling::TickTimes;
+use render::TextStyle;
+use resize::AutoGrow;
+use rewind::Rewind;
+use rng::Rng;
+use rules::Rule;
+use tiles::{Tiles, TILE_SIZE};
+use trail::Trail;
+use undo::UndoHistory;
+
+pub use draw::CellCoord;
+#[cfg(feature = "web")]
+pub use game_loop::GameLoop;
+pub use presets::{preset_description, RulePreset};
+pub use profiling::TickStats;
+pub use sparse::SparseUniverse;
+
+// Macro for logging to browser console, or standard output natively
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
-        console::log_1(&format!( $( $t )* ).into());
+        platform::log(&format!( $( $t )* ))
     }
 }
 
 // Import the `console.log` function from the browser
+#[cfg(feature = "web")]
 #[wasm_bindgen]
 extern "C" {
     fn alert(s: &str);
 }
 
+// Natively there's no one to alert, so just print the message
+#[cfg(not(feature = "web"))]
+fn alert(s: &str) {
+    println!("{}", s);
+}
+
 // Export a `greet` function from Rust to JavaScript, which alerts a hello message
 #[wasm_bindgen]
 pub fn greet(name: &str) {
@@ -24,52 +132,169 @@ pub fn greet(name: &str) {
 #[wasm_bindgen]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cell {
     Dead = 0,
     Alive = 1,
 }
 
-impl Cell {
-    fn toggle(&mut self) {
-        *self = match *self {
-            Cell::Dead => Cell::Alive,
-            Cell::Alive => Cell::Dead,
-        };
+// Cell states as stored in the universe. Generations rules add dying states
+// numbered from 2 up to one less than the rule's state count.
+const DEAD: u8 = Cell::Dead as u8;
+const ALIVE: u8 = Cell::Alive as u8;
+
+// Flip a cell between dead and alive; a dying cell counts as dead.
+fn toggled(state: u8) -> u8 {
+    if state == ALIVE {
+        DEAD
+    } else {
+        ALIVE
+    }
+}
+
+// The most cells a universe may hold, keeping every index well inside `u32`.
+const MAX_CELLS: u32 = 1 << 28;
+
+// Check that a universe of the given size has at least one cell and no more
+// than `MAX_CELLS`, returning its cell count.
+fn check_dimensions(width: u32, height: u32) -> Result<usize, String> {
+    if width == 0 || height == 0 {
+        return Err(format!(
+            "Universe must be at least 1x1, not {}x{}",
+            width, height
+        ));
+    }
+    match width.checked_mul(height) {
+        Some(cells) if cells <= MAX_CELLS => Ok(cells as usize),
+        _ => Err(format!(
+            "Universe of {}x{} is too large; it may hold at most {} cells",
+            width, height, MAX_CELLS
+        )),
     }
 }
 
+// How a placed pattern combines with the cells already on the board: Replace
+// clears the pattern's bounding box first, Or only adds live cells, and Xor
+// toggles the cells under the pattern's live cells.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlacementMode {
+    Replace = 0,
+    Or = 1,
+    Xor = 2,
+}
+
+// Bounding box of a pattern, as returned by `Universe::pattern_size`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatternSize {
+    pub width: u32,
+    pub height: u32,
+}
+
 // Main Universe struct representing the Game of Life world
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Universe {
     width: u32,
     height: u32,
-    cells: Vec<Cell>,
+    cells: Vec<u8>,
+    // The previous generation's buffer, reused to hold the next one.
+    next: Vec<u8>,
+    // Buffer kept between calls to `tick_delta`.
+    delta: Vec<u32>,
+    // The cells before the last step, copied only while ants are walking.
+    delta_base: Vec<u8>,
+    // Buffer kept between calls to `live_cells`.
+    live_coords: Vec<u32>,
+    // Buffer kept between calls to `render_rgba_into_image_data`.
+    #[cfg(feature = "web")]
+    rgba: Vec<u8>,
+    tiles: Tiles,
+    // Births, deaths and survivals in the last generation.
+    changes: Changes,
+    population_history: PopulationHistory,
+    // Generations each cell has been alive, while tracked.
+    ages: Option<Vec<u16>>,
+    // How many generations changed each cell, while counted.
+    heatmap: Option<Vec<u32>>,
+    // Fading cells behind those that died, for display.
+    trail: Trail,
+    // Generations run since the universe was created, cleared or loaded.
+    generation: u64,
+    // Recent states for `detect_period`.
+    history: History,
+    undo: UndoHistory,
+    // Recent generations for `step_back`.
+    rewind: Rewind,
+    checkpoints: Checkpoints,
+    neighbor_counts: NeighborCounts,
+    // The live cells packed a bit each, while a two-state rule runs. This
+    // is a copy for stepping; `cells` is still the storage.
+    live_bits: LiveBits,
+    rule: Rule,
+    rule_history: Vec<Rule>,
+    allow_birth_on_zero: bool,
+    automaton: Automaton,
+    custom_rule: Option<CustomRule>,
+    neighborhood: Neighborhood,
+    boundary: Boundary,
+    auto_grow: Option<AutoGrow>,
+    resized: bool,
+    ants: Vec<Ant>,
+    ants_only: bool,
+    custom_patterns: Vec<(String, Pattern)>,
+    clipboard: Option<Pattern>,
+    selection: Option<selection::Selection>,
+    // The components `components` last found, and the board they were
+    // found on.
+    labels: Option<components::Labels>,
+    // Bumped whenever the cells move to a different buffer.
+    cells_ptr_generation: u32,
+    // Where the random cells and rule changes come from.
+    rng: Rng,
+    profiling: bool,
+    tick_times: TickTimes,
+    text_style: TextStyle,
+    draw_style: DrawStyle,
+    // The drag `begin_draw` started, until `end_draw`.
+    draw_session: Option<draw_session::DrawSession>,
+    // What `draw_delta` last painted.
+    #[cfg(feature = "web")]
+    drawn: Option<draw::Drawn>,
+    // Where `tick` saves to localStorage, if anywhere.
+    #[cfg(feature = "web")]
+    autosave: Option<storage::Autosave>,
+    // The JavaScript function `tick` reports to, if any.
+    #[cfg(feature = "web")]
+    on_tick: callbacks::OnTick,
+    // The JavaScript function `tick` calls when the board settles, if any.
+    #[cfg(feature = "web")]
+    on_settled: callbacks::OnSettled,
+    // Where `tick` dispatches `life:tick` events, if anywhere.
+    #[cfg(feature = "web")]
+    tick_events: Option<events::TickEvents>,
 }
 
 // Methods callable from JavaScript
 #[wasm_bindgen]
 impl Universe {
     pub fn new() -> Universe {
-        utils::set_panic_hook();
-        
         let width = 64;
         let height = 64;
 
         let cells = (0..width * height)
             .map(|i| {
                 if i % 2 == 0 || i % 7 == 0 {
-                    Cell::Alive
+                    ALIVE
                 } else {
-                    Cell::Dead
+                    DEAD
                 }
             })
             .collect();
 
-        Universe {
-            width,
-            height,
-            cells,
-        }
+        Universe::with_cells(width, height, cells)
     }
 
     pub fn width(&self) -> u32 {
@@ -80,141 +305,484 @@ impl Universe {
         self.height
     }
 
-    pub fn cells(&self) -> *const Cell {
+    /// Pointer to the cell states, one byte per cell in row-major order: 0 is
+    /// dead, 1 is alive and higher values are dying cells under a
+    /// Generations rule.
+    ///
+    /// Ticks alternate between two buffers, so the pointer changes from one
+    /// generation to the next; read it again after every tick, or whenever
+    /// `cells_ptr_generation` changes.
+    pub fn cells(&self) -> *const u8 {
         self.cells.as_ptr()
     }
 
+    /// A counter that changes whenever the pointer returned by `cells` may
+    /// have changed, such as after a tick or a resize, so JavaScript knows
+    /// when to rebuild its view of the cells.
+    pub fn cells_ptr_generation(&self) -> u32 {
+        self.cells_ptr_generation
+    }
+
+    /// Length in bytes of the buffer behind `cells`.
+    pub fn cells_len_bytes(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The live cells packed one bit per cell in row-major order: cell `i` is
+    /// bit `i % 8` of byte `i / 8`, counting from the least significant bit.
+    /// Dying cells and other states read as dead.
+    ///
+    /// This is a copy an eighth the size of `cells`, for sending boards
+    /// elsewhere; cells are stored a byte each because most automata have
+    /// more than two states.
+    pub fn live_bits(&self) -> Vec<u8> {
+        let mut bits = Vec::new();
+        self.pack_live_into(&mut bits);
+        bits
+    }
+
+    /// Every live cell as a flat `[row0, col0, row1, col1, ...]` array, in
+    /// row-major order, for drawing sparse boards without scanning `cells`.
+    pub fn live_cells(&mut self) -> js_sys::Uint32Array {
+        self.live_cells_in(0, 0, self.height, self.width)
+    }
+
+    /// The live cells within the `width` by `height` rectangle whose top-left
+    /// corner is at the given position, as with `live_cells`. Coordinates
+    /// stay relative to the universe, and any part of the rectangle beyond
+    /// its edges is ignored.
+    pub fn live_cells_in(
+        &mut self,
+        row: u32,
+        col: u32,
+        height: u32,
+        width: u32,
+    ) -> js_sys::Uint32Array {
+        self.live_coords.clear();
+        let rows = row.min(self.height)..row.saturating_add(height).min(self.height);
+        let cols = col.min(self.width)..col.saturating_add(width).min(self.width);
+        for r in rows {
+            for c in cols.clone() {
+                if self.state(self.get_index(r, c)) == ALIVE {
+                    self.live_coords.extend([r, c]);
+                }
+            }
+        }
+        js_sys::Uint32Array::from(&self.live_coords[..])
+    }
+
     /// Set the width of the universe.
     ///
-    /// Resets all cells to the dead state.
-    pub fn set_width(&mut self, width: u32) {
+    /// Resets all cells to the dead state; use `resize` to keep them. Throws
+    /// an error if the width is zero or the universe would be too large.
+    pub fn set_width(&mut self, width: u32) -> Result<(), JsValue> {
+        let size = check_dimensions(width, self.height).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
         self.width = width;
-        self.cells = (0..width * self.height).map(|_i| Cell::Dead).collect();
+        self.reset_cells(size);
+        Ok(())
     }
 
     /// Set the height of the universe.
     ///
-    /// Resets all cells to the dead state.
-    pub fn set_height(&mut self, height: u32) {
+    /// Resets all cells to the dead state; use `resize` to keep them. Throws
+    /// an error if the height is zero or the universe would be too large.
+    pub fn set_height(&mut self, height: u32) -> Result<(), JsValue> {
+        let size = check_dimensions(self.width, height).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
         self.height = height;
-        self.cells = (0..self.width * height).map(|_i| Cell::Dead).collect();
+        self.reset_cells(size);
+        Ok(())
     }
 
     pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        self.remember();
         let idx = self.get_index(row, column);
-        self.cells[idx].toggle();
-    }
-
-    pub fn set_pattern(&mut self, pattern: &str, start_row: u32, start_col: u32) {
-        match pattern {
-            "glider" => {
-                let pattern = [
-                    (0, 1), (1, 2), (2, 0), (2, 1), (2, 2)
-                ];
-                self.clear_area(start_row, start_col, 3, 3);
-                for &(row, col) in &pattern {
-                    let r = (start_row + row) % self.height;
-                    let c = (start_col + col) % self.width;
-                    let idx = self.get_index(r, c);
-                    self.cells[idx] = Cell::Alive;
-                }
+        let state = match self.automaton {
+            Automaton::Wireworld => wireworld::toggled(self.state(idx)),
+            _ => toggled(self.state(idx)),
+        };
+        self.set_state(idx, state);
+    }
+
+    /// Whether the cell at the given position is alive; dying cells count as
+    /// dead. Throws an error if the position is outside the universe.
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<Cell, JsValue> {
+        let idx = self.checked_index(row, col)?;
+        Ok(if self.state(idx) == ALIVE {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        })
+    }
+
+    /// Make the cell at the given position dead or alive. Throws an error if
+    /// the position is outside the universe.
+    pub fn set_cell(&mut self, row: u32, col: u32, cell: Cell) -> Result<(), JsValue> {
+        let idx = self.checked_index(row, col)?;
+        if self.state(idx) != cell as u8 {
+            self.remember();
+            self.set_state(idx, cell as u8);
+        }
+        Ok(())
+    }
+
+    /// Bring the cells in a flat `[row0, col0, row1, col1, ...]` array to
+    /// life.
+    ///
+    /// All or nothing: if the array has odd length or any pair lies outside
+    /// the universe, an error naming its index is thrown and no cell is
+    /// changed.
+    pub fn set_cells_alive(&mut self, coords: &[u32]) -> Result<(), JsValue> {
+        self.set_cells(coords, ALIVE)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Kill the cells in a flat `[row0, col0, row1, col1, ...]` array, all or
+    /// nothing as with `set_cells_alive`.
+    pub fn set_cells_dead(&mut self, coords: &[u32]) -> Result<(), JsValue> {
+        self.set_cells(coords, DEAD)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Place a pattern by name with its top-left corner at the given position.
+    ///
+    /// Names are matched case-insensitively after trimming whitespace. An
+    /// unknown name throws an error suggesting the closest known pattern.
+    /// In Wireworld the circuits `clock` and `diode` are also available.
+    pub fn set_pattern(
+        &mut self,
+        pattern: &str,
+        start_row: u32,
+        start_col: u32,
+    ) -> Result<(), JsValue> {
+        self.set_pattern_oriented(pattern, start_row, start_col, 0, false, false)
+    }
+
+    /// Place a pattern after mirroring it and rotating it clockwise by
+    /// `rotation` quarter turns (so 1 is 90 degrees and 3 is 270 degrees).
+    ///
+    /// `flip_h` mirrors the pattern left to right and `flip_v` top to bottom.
+    /// Mirroring is applied before rotation, and the cleared area is rotated
+    /// along with the pattern.
+    pub fn set_pattern_oriented(
+        &mut self,
+        pattern: &str,
+        start_row: u32,
+        start_col: u32,
+        rotation: u8,
+        flip_h: bool,
+        flip_v: bool,
+    ) -> Result<(), JsValue> {
+        if self.automaton == Automaton::Wireworld {
+            if let Some((circuit, states)) = wireworld::circuit(pattern) {
+                let oriented = circuit.transformed(rotation, flip_h, flip_v);
+                self.remember();
+                self.place_circuit(&oriented, &states, start_row, start_col);
+                return Ok(());
             }
-            "pulsar" => {
-                let pattern = [
-                    // Top part
-                    (2, 4), (2, 5), (2, 6), (2, 10), (2, 11), (2, 12),
-                    (4, 2), (4, 7), (4, 9), (4, 14),
-                    (5, 2), (5, 7), (5, 9), (5, 14),
-                    (6, 2), (6, 7), (6, 9), (6, 14),
-                    (7, 4), (7, 5), (7, 6), (7, 10), (7, 11), (7, 12),
-                    // Bottom part
-                    (9, 4), (9, 5), (9, 6), (9, 10), (9, 11), (9, 12),
-                    (10, 2), (10, 7), (10, 9), (10, 14),
-                    (11, 2), (11, 7), (11, 9), (11, 14),
-                    (12, 2), (12, 7), (12, 9), (12, 14),
-                    (14, 4), (14, 5), (14, 6), (14, 10), (14, 11), (14, 12),
-                ];
-                self.clear_area(start_row, start_col, 17, 17);
-                for &(row, col) in &pattern {
-                    let r = (start_row + row) % self.height;
-                    let c = (start_col + col) % self.width;
-                    let idx = self.get_index(r, c);
-                    self.cells[idx] = Cell::Alive;
-                }
+        }
+        let found = self.find_named_pattern(pattern)?;
+        let oriented = found.transformed(rotation, flip_h, flip_v);
+        self.remember();
+        self.place(&oriented, start_row, start_col, PlacementMode::Replace);
+        Ok(())
+    }
+
+    /// Place a pattern, combining it with the existing cells according to
+    /// `mode` instead of always clearing its bounding box first.
+    pub fn set_pattern_with_mode(
+        &mut self,
+        pattern: &str,
+        start_row: u32,
+        start_col: u32,
+        mode: PlacementMode,
+    ) -> Result<(), JsValue> {
+        let found = self.find_named_pattern(pattern)?;
+        self.remember();
+        self.place(&found, start_row, start_col, mode);
+        Ok(())
+    }
+
+    /// Place a pattern like `set_pattern`, but throw an error instead of
+    /// wrapping when it would extend past the right or bottom edge.
+    ///
+    /// The universe is left untouched when the pattern doesn't fit.
+    pub fn set_pattern_strict(
+        &mut self,
+        pattern: &str,
+        start_row: u32,
+        start_col: u32,
+    ) -> Result<(), JsValue> {
+        let found = self.find_named_pattern(pattern)?;
+        let fits = |start: u32, extent: u32, size: u32| {
+            start.checked_add(extent).is_some_and(|end| end <= size)
+        };
+        if !fits(start_row, found.height, self.height) || !fits(start_col, found.width, self.width)
+        {
+            return Err(JsValue::from_str(&format!(
+                "Pattern '{}' ({}x{}) placed at row {}, column {} does not fit in the {}x{} universe",
+                pattern.trim(),
+                found.width,
+                found.height,
+                start_row,
+                start_col,
+                self.width,
+                self.height
+            )));
+        }
+        self.remember();
+        self.place(&found, start_row, start_col, PlacementMode::Replace);
+        Ok(())
+    }
+
+    /// Place a pattern so that its bounding box is centered on the given
+    /// cell, wrapping around the edges when it extends past them (or cut
+    /// off there unless the boundary is a torus).
+    ///
+    /// For even extents the extra row or column falls above or to the left
+    /// of the center, so a 38-wide pattern has 19 columns left of the
+    /// center column and 18 to its right.
+    pub fn set_pattern_centered(
+        &mut self,
+        pattern: &str,
+        center_row: u32,
+        center_col: u32,
+    ) -> Result<(), JsValue> {
+        let found = self.find_named_pattern(pattern)?;
+        let start_row = i64::from(center_row) - i64::from(found.height / 2);
+        let start_col = i64::from(center_col) - i64::from(found.width / 2);
+        self.remember();
+        self.place_at(&found, start_row, start_col, PlacementMode::Replace);
+        Ok(())
+    }
+
+    /// Place a spaceship aimed in the given direction.
+    ///
+    /// `kind` is `glider`, which travels diagonally (`ne`, `nw`, `se` or
+    /// `sw`), or one of `lwss`, `mwss` and `hwss`, which travel orthogonally
+    /// (`n`, `s`, `e` or `w`).
+    pub fn spawn_ship(
+        &mut self,
+        kind: &str,
+        row: u32,
+        col: u32,
+        direction: &str,
+    ) -> Result<(), JsValue> {
+        let kind = kind.trim().to_lowercase();
+        let direction = direction.trim().to_lowercase();
+
+        // The library glider travels south-east and the spaceships west.
+        let (rotation, flip_h, flip_v) = match (kind.as_str(), direction.as_str()) {
+            ("glider", "se") => (0, false, false),
+            ("glider", "sw") => (0, true, false),
+            ("glider", "ne") => (0, false, true),
+            ("glider", "nw") => (2, false, false),
+            ("lwss" | "mwss" | "hwss", "w") => (0, false, false),
+            ("lwss" | "mwss" | "hwss", "e") => (0, true, false),
+            ("lwss" | "mwss" | "hwss", "n") => (1, false, false),
+            ("lwss" | "mwss" | "hwss", "s") => (3, false, false),
+            ("glider", _) => {
+                return Err(JsValue::from_str(&format!(
+                    "Gliders travel diagonally; direction must be ne, nw, se or sw, not '{}'",
+                    direction
+                )))
             }
-            "gosper_glider_gun" => {
-                let pattern = [
-                    (1, 25),
-                    (2, 23), (2, 25),
-                    (3, 13), (3, 14), (3, 21), (3, 22), (3, 35), (3, 36),
-                    (4, 12), (4, 16), (4, 21), (4, 22), (4, 35), (4, 36),
-                    (5, 1), (5, 2), (5, 11), (5, 17), (5, 21), (5, 22),
-                    (6, 1), (6, 2), (6, 11), (6, 15), (6, 17), (6, 18), (6, 23), (6, 25),
-                    (7, 11), (7, 17), (7, 25),
-                    (8, 12), (8, 16),
-                    (9, 13), (9, 14),
-                ];
-                self.clear_area(start_row, start_col, 11, 38);
-                for &(row, col) in &pattern {
-                    let r = (start_row + row) % self.height;
-                    let c = (start_col + col) % self.width;
-                    let idx = self.get_index(r, c);
-                    self.cells[idx] = Cell::Alive;
-                }
+            ("lwss" | "mwss" | "hwss", _) => {
+                return Err(JsValue::from_str(&format!(
+                    "Spaceships travel orthogonally; direction must be n, s, e or w, not '{}'",
+                    direction
+                )))
             }
-            _ => log!("Unknown pattern: {}", pattern),
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Unknown ship '{}'; expected glider, lwss, mwss or hwss",
+                    kind
+                )))
+            }
+        };
+
+        self.set_pattern_oriented(&kind, row, col, rotation, flip_h, flip_v)
+    }
+
+    /// Names of all patterns accepted by `set_pattern`: the built-in library
+    /// in order, followed by registered patterns in registration order.
+    pub fn pattern_names(&self) -> Vec<JsValue> {
+        self.known_pattern_names()
+            .iter()
+            .map(|name| JsValue::from_str(name))
+            .collect()
+    }
+
+    /// Bounding box of a built-in or registered pattern, so callers can check
+    /// whether it fits before placing it.
+    pub fn pattern_size(&self, name: &str) -> Result<PatternSize, JsValue> {
+        let pattern = self.find_named_pattern(name)?;
+        Ok(PatternSize {
+            width: pattern.width,
+            height: pattern.height,
+        })
+    }
+
+    /// Describe a built-in or registered pattern as an object with its
+    /// `name`, `width`, `height` and `category`.
+    ///
+    /// Methuselahs also carry a `lifespan`: the known number of generations
+    /// before they stabilize.
+    pub fn pattern_info(&self, name: &str) -> Result<JsValue, JsValue> {
+        let found = self.find_named_pattern(name)?;
+        let registered = self
+            .custom_patterns
+            .iter()
+            .any(|(n, _)| pattern::same_name(n, name));
+        let category = match pattern::builtin_category(name) {
+            Some(category) if !registered => category,
+            _ => Category::Custom,
+        };
+
+        let info = js_sys::Object::new();
+        let set = |key: &str, value: JsValue| js_sys::Reflect::set(&info, &key.into(), &value);
+        set("name", name.trim().to_lowercase().into())?;
+        set("width", found.width.into())?;
+        set("height", found.height.into())?;
+        set("category", category.name().into())?;
+        if let Some(lifespan) = category.lifespan() {
+            set("lifespan", lifespan.into())?;
         }
+        Ok(info.into())
+    }
+
+    /// Register a pattern so it can be placed by name with `set_pattern`.
+    ///
+    /// `cells` is a flat list of `row, col` pairs for the live cells, each
+    /// within the declared `width` and `height`. An existing name, including
+    /// a built-in one, is only replaced when `overwrite` is set.
+    pub fn register_pattern(
+        &mut self,
+        name: &str,
+        width: u32,
+        height: u32,
+        cells: &[u32],
+        overwrite: bool,
+    ) -> Result<(), JsValue> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(JsValue::from_str("Pattern name must not be empty"));
+        }
+        if !overwrite && self.lookup_pattern(name).is_some() {
+            return Err(JsValue::from_str(&format!(
+                "Pattern '{}' already exists; pass overwrite to replace it",
+                name
+            )));
+        }
+        if !cells.len().is_multiple_of(2) {
+            return Err(JsValue::from_str(
+                "Pattern cells must be a flat list of row, col pairs",
+            ));
+        }
+        if let Some(pair) = cells
+            .chunks(2)
+            .find(|pair| pair[0] >= height || pair[1] >= width)
+        {
+            return Err(JsValue::from_str(&format!(
+                "Cell ({}, {}) lies outside the declared {}x{} pattern",
+                pair[0], pair[1], width, height
+            )));
+        }
+
+        let pattern = Pattern {
+            width,
+            height,
+            cells: cells.chunks(2).map(|pair| (pair[0], pair[1])).collect(),
+        };
+        let existing = self
+            .custom_patterns
+            .iter_mut()
+            .find(|(n, _)| pattern::same_name(n, name));
+        match existing {
+            Some(entry) => entry.1 = pattern,
+            None => self.custom_patterns.push((name.to_string(), pattern)),
+        }
+        Ok(())
     }
 
     pub fn clear(&mut self) {
-        self.cells = vec![Cell::Dead; (self.width * self.height) as usize];
+        self.remember();
+        self.cells.fill(DEAD);
+        self.cells_rewritten();
+        self.changes = Changes::default();
+        self.generation = 0;
     }
 
     pub fn randomize(&mut self) {
-        self.cells = (0..self.width * self.height)
-            .map(|_| {
-                if js_sys::Math::random() < 0.3 {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            })
-            .collect();
+        self.remember();
+        for idx in 0..self.cells.len() {
+            let state = if self.rng.next_f64() < 0.3 {
+                ALIVE
+            } else {
+                DEAD
+            };
+            self.set_state(idx, state);
+        }
+        self.changes = Changes::default();
     }
 
     pub fn tick(&mut self) {
-        let _timer = Timer::new("Universe::tick");
+        let _timer = self.profiling.then(|| Timer::new("Universe::tick"));
+        let started = platform::now();
         
-        let mut next = self.cells.clone();
-
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-                let live_neighbors = self.live_neighbor_count(row, col);
-
-                let next_cell = match (cell, live_neighbors) {
-                    // Rule 1: Any live cell with fewer than two live neighbours
-                    // dies, as if caused by underpopulation.
-                    (Cell::Alive, x) if x < 2 => Cell::Dead,
-                    // Rule 2: Any live cell with two or three live neighbours
-                    // lives on to the next generation.
-                    (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                    // Rule 3: Any live cell with more than three live
-                    // neighbours dies, as if by overpopulation.
-                    (Cell::Alive, x) if x > 3 => Cell::Dead,
-                    // Rule 4: Any dead cell with exactly three live neighbours
-                    // becomes a live cell, as if by reproduction.
-                    (Cell::Dead, 3) => Cell::Alive,
-                    // All other cells remain in the same state.
-                    (otherwise, _) => otherwise,
-                };
-
-                next[idx] = next_cell;
-            }
+        #[cfg(feature = "web")]
+        self.watch_settled();
+        self.remember();
+        self.save_generation();
+        self.step_counted();
+        self.generation += 1;
+        if let Some(growth) = self.auto_grow {
+            self.grow_to_fit(growth);
         }
+        self.record_history();
+        self.save_checkpoint();
+        self.finish_timing(started, 1);
+        #[cfg(feature = "web")]
+        {
+            self.autosave_after(1);
+            self.after_generation();
+            self.after_ticks();
+        }
+    }
+
+    /// Advance up to `n` generations in one call, stopping early once the
+    /// universe is empty or stops changing. Returns the number of
+    /// generations actually run.
+    ///
+    /// The whole batch is timed as one `Universe::tick_n` measurement.
+    pub fn tick_n(&mut self, n: u32) -> u32 {
+        let _timer = self.profiling.then(|| Timer::new("Universe::tick_n"));
+        let started = platform::now();
+
+        #[cfg(feature = "web")]
+        self.watch_settled();
+        self.remember();
+        let ran = self.run_until_settled(n);
+        self.finish_timing(started, ran);
+        #[cfg(feature = "web")]
+        {
+            self.autosave_after(ran);
+            self.after_ticks();
+        }
+        ran
+    }
 
-        self.cells = next;
+    /// The number of generations run since the universe was created,
+    /// cleared, resized with `set_width` or `set_height`, or loaded from a
+    /// pattern file.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Set the generation counter, such as when restoring a saved session.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
     }
 
     pub fn render(&self) -> String {
@@ -224,79 +792,531 @@ impl Universe {
 
 // Private methods (not exported to JavaScript)
 impl Universe {
+    fn with_cells(width: u32, height: u32, cells: Vec<u8>) -> Universe {
+        utils::set_panic_hook();
+
+        Universe {
+            width,
+            height,
+            next: vec![DEAD; cells.len()],
+            cells,
+            delta: Vec::new(),
+            delta_base: Vec::new(),
+            live_coords: Vec::new(),
+            #[cfg(feature = "web")]
+            rgba: Vec::new(),
+            tiles: Tiles::default(),
+            changes: Changes::default(),
+            population_history: PopulationHistory::default(),
+            ages: None,
+            heatmap: None,
+            trail: Trail::default(),
+            generation: 0,
+            history: History::default(),
+            undo: UndoHistory::default(),
+            rewind: Rewind::default(),
+            checkpoints: Checkpoints::default(),
+            neighbor_counts: NeighborCounts::default(),
+            live_bits: LiveBits::default(),
+            rule: Rule::CONWAY,
+            rule_history: vec![Rule::CONWAY],
+            allow_birth_on_zero: false,
+            automaton: Automaton::Life,
+            custom_rule: None,
+            neighborhood: Neighborhood::Moore,
+            boundary: Boundary::Torus,
+            auto_grow: None,
+            resized: false,
+            ants: Vec::new(),
+            ants_only: false,
+            custom_patterns: Vec::new(),
+            clipboard: None,
+            selection: None,
+            labels: None,
+            cells_ptr_generation: 0,
+            rng: Rng::new(platform::random_seed()),
+            profiling: false,
+            tick_times: TickTimes::default(),
+            text_style: TextStyle::default(),
+            draw_style: DrawStyle::default(),
+            draw_session: None,
+            #[cfg(feature = "web")]
+            drawn: None,
+            #[cfg(feature = "web")]
+            autosave: None,
+            #[cfg(feature = "web")]
+            on_tick: callbacks::OnTick::default(),
+            #[cfg(feature = "web")]
+            on_settled: callbacks::OnSettled::default(),
+            #[cfg(feature = "web")]
+            tick_events: None,
+        }
+    }
+
     fn get_index(&self, row: u32, column: u32) -> usize {
         (row * self.width + column) as usize
     }
 
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-        
-        // Use modular arithmetic to handle wrapping at edges
-        for delta_row in [self.height - 1, 0, 1].iter().cloned() {
-            for delta_col in [self.width - 1, 0, 1].iter().cloned() {
-                if delta_row == 0 && delta_col == 0 {
-                    continue;
+    // Set every cell listed in a flat array of (row, column) pairs, checking
+    // them all before changing any.
+    fn set_cells(&mut self, coords: &[u32], state: u8) -> Result<(), String> {
+        if !coords.len().is_multiple_of(2) {
+            return Err(format!(
+                "Coordinates must come in (row, col) pairs, but {} numbers were given",
+                coords.len()
+            ));
+        }
+        if let Some(pair) = coords
+            .chunks_exact(2)
+            .position(|pair| pair[0] >= self.height || pair[1] >= self.width)
+        {
+            return Err(format!(
+                "Cell ({}, {}) at index {} is outside the {}x{} universe",
+                coords[pair * 2],
+                coords[pair * 2 + 1],
+                pair * 2,
+                self.width,
+                self.height
+            ));
+        }
+        self.remember();
+        for pair in coords.chunks_exact(2) {
+            let idx = self.get_index(pair[0], pair[1]);
+            self.set_state(idx, state);
+        }
+        Ok(())
+    }
+
+    // The index of the cell at the given position, or an error if it lies
+    // outside the universe.
+    fn checked_index(&self, row: u32, col: u32) -> Result<usize, JsValue> {
+        if row >= self.height || col >= self.width {
+            return Err(JsValue::from_str(&format!(
+                "Cell ({}, {}) is outside the {}x{} universe",
+                row, col, self.width, self.height
+            )));
+        }
+        Ok(self.get_index(row, col))
+    }
+
+    // Fill `bits` with the live cells as `live_bits` returns them, reusing
+    // its allocation.
+    fn pack_live_into(&self, bits: &mut Vec<u8>) {
+        bits.clear();
+        bits.resize(self.cells.len().div_ceil(8), 0);
+        for idx in (0..self.cells.len()).filter(|&idx| self.state(idx) == ALIVE) {
+            bits[idx / 8] |= 1 << (idx % 8);
+        }
+    }
+
+    // The state of the cell at `idx`. Code outside the tick loops reads and
+    // writes cells through these two methods, so the storage can change
+    // without touching it.
+    fn state(&self, idx: usize) -> u8 {
+        self.cells[idx]
+    }
+
+    fn set_state(&mut self, idx: usize, state: u8) {
+        let was = self.cells[idx];
+        if was != state {
+            self.mark_edited(idx);
+            if (was == ALIVE) != (state == ALIVE) {
+                self.count_change(idx, state == ALIVE);
+            }
+            self.bit_change(idx, state);
+        }
+        self.cells[idx] = state;
+        if let Some(ages) = &mut self.ages {
+            ages[idx] = u16::from(state == ALIVE);
+        }
+    }
+
+    // Advance one generation without any of `tick`'s bookkeeping.
+    fn step(&mut self) {
+        // Only the per-cell loop below keeps track of the tiles.
+        let previous = self.tiles.last_step.take();
+
+        if !self.ants.is_empty() {
+            self.move_ants();
+            if self.ants_only {
+                return;
+            }
+        }
+
+        // Automata with loops of their own change the cells without keeping
+        // the neighbour counts.
+        let counted = self.custom_rule.is_none()
+            && matches!(
+                self.automaton,
+                Automaton::Life | Automaton::Wireworld | Automaton::Immigration
+            );
+        if !counted {
+            self.cells_rewritten();
+        }
+
+        if let Some(rule) = self.custom_rule.clone() {
+            // Throwing here would leave the universe borrowed, so report
+            // the error instead.
+            if let Err(error) = self.tick_custom(&rule) {
+                #[cfg(feature = "web")]
+                web_sys::console::error_2(&"Custom rule failed:".into(), &error);
+                #[cfg(not(feature = "web"))]
+                eprintln!("Custom rule failed: {:?}", error);
+            }
+            return;
+        }
+
+        match self.automaton {
+            Automaton::Elementary(rule) => return self.tick_elementary(rule),
+            Automaton::LargerThanLife(rule) => return self.tick_ltl(rule),
+            Automaton::Map(rule) => return self.tick_map(rule),
+            _ => {}
+        }
+
+        // Inactive tiles are the same in both buffers, so skipping them
+        // leaves the right cells in `next`.
+        self.mark_active_tiles(previous);
+        let mut next = self.take_next();
+        if self.steps_packed() && self.sync_bits() {
+            // The packed step counts neighbours as it goes, so the table
+            // isn't kept.
+            self.neighbor_counts.layout = None;
+            self.neighbor_counts.counts = Vec::new();
+            self.step_packed(&mut next);
+            self.swap_in(next);
+        } else {
+            self.drop_bits();
+            self.sync_counts();
+            let mut changed = std::mem::take(&mut self.neighbor_counts.changed);
+            changed.clear();
+            match self.automaton {
+                #[cfg(feature = "parallel")]
+                Automaton::Life => self.step_life_parallel(&mut next, &mut changed),
+                _ => self.step_tiles(&mut next, &mut changed),
+            }
+            self.swap_in(next);
+            self.count_changes(&changed);
+            self.neighbor_counts.changed = changed;
+        }
+        self.tiles.last_step = Some(self.step_config());
+    }
+
+    // Compute the next state of every cell in the active tiles, listing
+    // those that come to life or die in `changed`.
+    fn step_tiles(&self, next: &mut [u8], changed: &mut Vec<u32>) {
+        let across = self.width.div_ceil(TILE_SIZE);
+
+        for (tile, _) in self.tiles.active.iter().enumerate().filter(|(_, &a)| a) {
+            let top = tile as u32 / across * TILE_SIZE;
+            let left = tile as u32 % across * TILE_SIZE;
+            for row in top..(top + TILE_SIZE).min(self.height) {
+                for col in left..(left + TILE_SIZE).min(self.width) {
+                    let idx = self.get_index(row, col);
+                    let cell = self.cells[idx];
+                    let live_neighbors = self.neighbor_counts.counts[idx];
+
+                    // For Life the birth and survival sets decide the next
+                    // state; with the default B3/S23 these are Conway's rules.
+                    next[idx] = match self.automaton {
+                        Automaton::Wireworld => wireworld::next(cell, live_neighbors),
+                        Automaton::Immigration => self.immigration_next(row, col),
+                        _ => self.rule.next(cell, live_neighbors),
+                    };
+                    if (next[idx] == ALIVE) != (cell == ALIVE) {
+                        changed.push(idx as u32);
+                    }
                 }
+            }
+        }
+    }
 
-                let neighbor_row = (row + delta_row) % self.height;
-                let neighbor_col = (column + delta_col) % self.width;
-                let idx = self.get_index(neighbor_row, neighbor_col);
-                count += self.cells[idx] as u8;
+    // Step up to `n` times, stopping once the universe settles, and return
+    // the number of steps taken.
+    fn run_until_settled(&mut self, n: u32) -> u32 {
+        for generation in 0..n {
+            if self.run_generation() {
+                return generation + 1;
             }
         }
-        count
+        n
+    }
+
+    // Run one generation and everything recorded or reported after each,
+    // returning whether it left the board settled.
+    fn run_generation(&mut self) -> bool {
+        self.save_generation();
+        self.step_counted();
+        self.generation += 1;
+        let settled = self.settled();
+        if let Some(growth) = self.auto_grow {
+            self.grow_to_fit(growth);
+        }
+        self.record_history();
+        self.save_checkpoint();
+        #[cfg(feature = "web")]
+        self.after_generation();
+        settled
+    }
+
+    // Whether the last step left nothing more to happen: every cell is dead
+    // or unchanged, and no ants are walking.
+    fn settled(&self) -> bool {
+        self.ants.is_empty()
+            && (self.cells == self.next || self.cells.iter().all(|&cell| cell == DEAD))
+    }
+
+    // Note that the cells were rewritten all at once outside the tick loops,
+    // so the next step can't build on what the last one found.
+    fn cells_rewritten(&mut self) {
+        self.tiles.last_step = None;
+        self.neighbor_counts.layout = None;
+        self.drop_bits();
+    }
+
+    // Replace the cells with a new set, such as after a change of size,
+    // leaving a matching spare buffer.
+    fn replace_cells(&mut self, cells: Vec<u8>) {
+        self.next.clear();
+        self.next.resize(cells.len(), DEAD);
+        self.cells = cells;
+        self.cells_rewritten();
+        self.cells_ptr_generation = self.cells_ptr_generation.wrapping_add(1);
+        self.reset_ages();
     }
 
-    fn clear_area(&mut self, start_row: u32, start_col: u32, height: u32, width: u32) {
+    // Make every cell dead, keeping both buffers' allocations when they are
+    // large enough and otherwise growing them to exactly `size`.
+    fn reset_cells(&mut self, size: usize) {
+        let before = self.cells.as_ptr();
+        for buffer in [&mut self.cells, &mut self.next] {
+            buffer.clear();
+            buffer.reserve_exact(size);
+            buffer.resize(size, DEAD);
+        }
+        self.cells_rewritten();
+        self.generation = 0;
+        if self.cells.as_ptr() != before {
+            self.cells_ptr_generation = self.cells_ptr_generation.wrapping_add(1);
+        }
+        self.reset_ages();
+    }
+
+    // The spare buffer, sized to match the cells, for a tick to write the
+    // next generation into before handing it to `swap_in`.
+    fn take_next(&mut self) -> Vec<u8> {
+        let mut next = std::mem::take(&mut self.next);
+        next.resize(self.cells.len(), DEAD);
+        next
+    }
+
+    // Make `next` the current generation, keeping the old cells as the spare
+    // buffer so that ticking doesn't allocate.
+    fn swap_in(&mut self, next: Vec<u8>) {
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.cells_ptr_generation = self.cells_ptr_generation.wrapping_add(1);
+    }
+
+    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        self.neighbor_indices(row, column)
+            .filter(|&idx| self.cells[idx] == ALIVE)
+            .count() as u8
+    }
+
+    // Find a pattern by name, preferring registered patterns over the
+    // built-in library.
+    fn lookup_pattern(&self, name: &str) -> Option<Pattern> {
+        self.custom_patterns
+            .iter()
+            .find(|(n, _)| pattern::same_name(n, name))
+            .map(|(_, pattern)| pattern.clone())
+            .or_else(|| pattern::builtin(name))
+    }
+
+    // Built-in names not shadowed by a registered pattern, followed by the
+    // registered names.
+    fn known_pattern_names(&self) -> Vec<String> {
+        let shadowed = |name: &str| {
+            self.custom_patterns
+                .iter()
+                .any(|(n, _)| pattern::same_name(n, name))
+        };
+        pattern::builtin_names()
+            .filter(|name| !shadowed(name))
+            .map(String::from)
+            .chain(self.custom_patterns.iter().map(|(name, _)| name.clone()))
+            .collect()
+    }
+
+    // Like `lookup_pattern`, but an unknown name becomes an error for
+    // JavaScript that suggests the closest known name.
+    fn find_named_pattern(&self, name: &str) -> Result<Pattern, JsValue> {
+        self.lookup_pattern(name).ok_or_else(|| {
+            JsValue::from_str(&pattern::unknown_name(name, &self.known_pattern_names()))
+        })
+    }
+
+    // Stamp a pattern with its top-left corner at the given position,
+    // wrapping at the edges or cutting it off, depending on the boundary.
+    fn place(&mut self, pattern: &Pattern, start_row: u32, start_col: u32, mode: PlacementMode) {
+        self.place_at(pattern, start_row.into(), start_col.into(), mode);
+    }
+
+    // Like `place`, but the start may lie above or left of the grid.
+    fn place_at(&mut self, pattern: &Pattern, start_row: i64, start_col: i64, mode: PlacementMode) {
+        if mode == PlacementMode::Replace {
+            self.clear_area(start_row, start_col, pattern.height, pattern.width);
+        }
+        for &(row, col) in &pattern.cells {
+            let Some(idx) = self.offset_index(start_row, start_col, row, col) else {
+                continue;
+            };
+            let state = match mode {
+                PlacementMode::Replace | PlacementMode::Or => ALIVE,
+                PlacementMode::Xor => toggled(self.state(idx)),
+            };
+            self.set_state(idx, state);
+        }
+    }
+
+    // Snapshot a rectangle of cells as a pattern, wrapping at the edges like
+    // `clear_area`. Cells off the edge of a universe that doesn't wrap are
+    // dead.
+    fn region_pattern(&self, start_row: u32, start_col: u32, height: u32, width: u32) -> Pattern {
+        let mut cells = Vec::new();
         for row in 0..height {
             for col in 0..width {
-                let r = (start_row + row) % self.height;
-                let c = (start_col + col) % self.width;
-                let idx = self.get_index(r, c);
-                self.cells[idx] = Cell::Dead;
+                let idx = self.offset_index(start_row.into(), start_col.into(), row, col);
+                if idx.is_some_and(|idx| self.cells[idx] == ALIVE) {
+                    cells.push((row, col));
+                }
+            }
+        }
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // Collect the live cells as a pattern trimmed to their bounding box.
+    fn live_pattern(&self) -> Pattern {
+        let live: Vec<(u32, u32)> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cells[self.get_index(row, col)] == ALIVE)
+            .collect();
+
+        let (min_row, max_row, min_col, max_col) = match live.first() {
+            None => {
+                return Pattern {
+                    width: 0,
+                    height: 0,
+                    cells: live,
+                }
             }
+            Some(&(row, col)) => live
+                .iter()
+                .fold((row, row, col, col), |(r0, r1, c0, c1), &(r, c)| {
+                    (r0.min(r), r1.max(r), c0.min(c), c1.max(c))
+                }),
+        };
+
+        Pattern {
+            width: max_col - min_col + 1,
+            height: max_row - min_row + 1,
+            cells: live
+                .into_iter()
+                .map(|(row, col)| (row - min_row, col - min_col))
+                .collect(),
         }
     }
+
+    fn clear_area(&mut self, start_row: i64, start_col: i64, height: u32, width: u32) {
+        // Only offsets that land on the grid, however large the area.
+        let cols = self.boundary.span(start_col, width, self.width);
+        for row in self.boundary.span(start_row, height, self.height) {
+            for col in cols.clone() {
+                if let Some(idx) = self.offset_index(start_row, start_col, row, col) {
+                    self.set_state(idx, DEAD);
+                }
+            }
+        }
+    }
+
+    // A copy to run ahead on, without anything that records, saves or
+    // resizes as it ticks.
+    fn scratch_copy(&self) -> Universe {
+        let mut copy = self.clone();
+        copy.undo = UndoHistory::default();
+        copy.set_step_back_capacity(0);
+        copy.checkpoints = Checkpoints::default();
+        copy.history = History::default();
+        copy.population_history = PopulationHistory::default();
+        copy.ages = None;
+        copy.heatmap = None;
+        copy.trail = Trail::default();
+        copy.auto_grow = None;
+        copy.profiling = false;
+        #[cfg(feature = "web")]
+        {
+            copy.autosave = None;
+            copy.take_callbacks();
+        }
+        copy
+    }
+}
+
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 use std::fmt;
 
 impl fmt::Display for Universe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
-                write!(f, "{}", symbol)?;
-            }
-            write!(f, "\n")?;
-        }
-
-        Ok(())
+        self.write_rows(f, 0..self.height, 0..self.width)
     }
 }
 
 // Performance timing utilities
 pub struct Timer<'a> {
     name: &'a str,
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    started: std::time::Instant,
 }
 
 impl<'a> Timer<'a> {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
     pub fn new(name: &'a str) -> Timer<'a> {
-        console::time_with_label(name);
+        web_sys::console::time_with_label(name);
         Timer { name }
     }
+
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    pub fn new(name: &'a str) -> Timer<'a> {
+        Timer {
+            name,
+            started: std::time::Instant::now(),
+        }
+    }
 }
 
 impl<'a> Drop for Timer<'a> {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
     fn drop(&mut self) {
-        console::time_end_with_label(self.name);
+        web_sys::console::time_end_with_label(self.name);
+    }
+
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed().as_secs_f64() * 1000.0;
+        println!("{}: {:.3}ms", self.name, elapsed);
     }
 }
 
 // Utility functions for better debugging
 pub mod utils {
-    use wasm_bindgen::prelude::*;
-
     // When the `console_error_panic_hook` feature is enabled, we can call the
     // `set_panic_hook` function at least once during initialization, and then
     // we will get better error messages if our code ever panics.
@@ -310,4 +1330,371 @@ pub mod utils {
 
     #[cfg(not(feature = "console_error_panic_hook"))]
     pub fn set_panic_hook() {}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::empty;
+
+    // The live cells, row by row.
+    fn live(universe: &Universe) -> Vec<(u32, u32)> {
+        (0..universe.height)
+            .flat_map(|row| (0..universe.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| universe.state(universe.get_index(row, col)) == ALIVE)
+            .collect()
+    }
+
+    // A block with its top-left corner at (2, 2) and a glider placed over
+    // it at (1, 1), sharing three of its cells.
+    fn glider_on_block(mode: PlacementMode) -> Vec<(u32, u32)> {
+        let mut universe = empty(8, 8);
+        universe.set_pattern("block", 2, 2).unwrap();
+        universe
+            .set_pattern_with_mode("glider", 1, 1, mode)
+            .unwrap();
+        live(&universe)
+    }
+
+    #[test]
+    fn replace_clears_under_the_pattern() {
+        assert_eq!(
+            glider_on_block(PlacementMode::Replace),
+            [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn or_keeps_what_was_there() {
+        assert_eq!(
+            glider_on_block(PlacementMode::Or),
+            [(1, 2), (2, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn xor_flips_the_pattern_cells() {
+        assert_eq!(
+            glider_on_block(PlacementMode::Xor),
+            [(1, 2), (2, 2), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn xor_twice_puts_the_block_back() {
+        let mut universe = empty(8, 8);
+        universe.set_pattern("block", 2, 2).unwrap();
+        for _ in 0..2 {
+            universe
+                .set_pattern_with_mode("glider", 1, 1, PlacementMode::Xor)
+                .unwrap();
+        }
+        assert_eq!(live(&universe), [(2, 2), (2, 3), (3, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn replace_clears_a_huge_pattern_once_over() {
+        let huge = Pattern {
+            width: u32::MAX,
+            height: u32::MAX,
+            cells: vec![(0, 0)],
+        };
+        let mut universe = empty(8, 8);
+        universe.set_pattern("block", 2, 2).unwrap();
+        universe.place(&huge, 5, 5, PlacementMode::Replace);
+        assert_eq!(live(&universe), [(5, 5)]);
+    }
+
+    #[test]
+    fn replace_clears_only_the_part_on_a_dead_board() {
+        let tall = Pattern {
+            width: 2,
+            height: 1 << 30,
+            cells: vec![(5, 0)],
+        };
+        let mut universe = empty(8, 8);
+        universe.set_boundary("dead").unwrap();
+        universe.set_pattern("block", 2, 2).unwrap();
+        universe.set_pattern("block", 5, 5).unwrap();
+        universe.place_at(&tall, -3, 1, PlacementMode::Replace);
+        assert_eq!(
+            live(&universe),
+            [(2, 1), (2, 3), (3, 3), (5, 5), (5, 6), (6, 5), (6, 6)]
+        );
+    }
+
+    #[test]
+    fn blinker_turns_back_and_forth() {
+        let mut universe = empty(5, 5);
+        universe.set_pattern("blinker", 2, 1).unwrap();
+        // Timing is logged natively too, whatever the features.
+        universe.set_profiling(true);
+        for _ in 0..3 {
+            assert_eq!(live(&universe), [(2, 1), (2, 2), (2, 3)]);
+            universe.tick();
+            assert_eq!(live(&universe), [(1, 2), (2, 2), (3, 2)]);
+            universe.tick();
+        }
+        assert_eq!(universe.generation(), 6);
+    }
+
+    #[test]
+    fn glider_crosses_the_torus() {
+        let mut universe = empty(8, 6);
+        universe.set_pattern("glider", 0, 0).unwrap();
+        let start = live(&universe);
+        assert_eq!(start, [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        // Every four generations it moves a cell down and to the right,
+        // so it is back after running round both ways.
+        for moved in 1..=24 {
+            universe.tick_n(4);
+            let mut expected: Vec<_> = start
+                .iter()
+                .map(|&(row, col)| ((row + moved) % 6, (col + moved) % 8))
+                .collect();
+            expected.sort_unstable();
+            assert_eq!(live(&universe), expected);
+        }
+        assert_eq!(live(&universe), start);
+    }
+
+    // The live cells moved `rows` down and `cols` right.
+    fn moved(cells: &[(u32, u32)], rows: i32, cols: i32) -> Vec<(u32, u32)> {
+        let mut moved: Vec<_> = cells
+            .iter()
+            .map(|&(row, col)| (row.wrapping_add_signed(rows), col.wrapping_add_signed(cols)))
+            .collect();
+        moved.sort_unstable();
+        moved
+    }
+
+    #[test]
+    fn oriented_gliders_travel_their_new_way() {
+        // The library glider heads down and to the right. Mirroring turns
+        // that to the left or up, and each quarter turn clockwise takes
+        // down-right to down-left, up-left and up-right.
+        for (rotation, flip_h, flip_v, rows, cols) in [
+            (0, false, false, 1, 1),
+            (1, false, false, 1, -1),
+            (2, false, false, -1, -1),
+            (3, false, false, -1, 1),
+            (0, true, false, 1, -1),
+            (0, false, true, -1, 1),
+            // Mirrored first, then turned.
+            (1, true, false, -1, -1),
+            (1, false, true, 1, 1),
+        ] {
+            let mut universe = empty(30, 30);
+            universe
+                .set_pattern_oriented("glider", 13, 13, rotation, flip_h, flip_v)
+                .unwrap();
+            let start = live(&universe);
+            universe.tick_n(8);
+            assert_eq!(
+                live(&universe),
+                moved(&start, 2 * rows, 2 * cols),
+                "rotation {rotation}, flip_h {flip_h}, flip_v {flip_v}"
+            );
+        }
+    }
+
+    #[test]
+    fn spawned_ships_move_their_way_each_period() {
+        // Every ship's period is 4; a glider moves a cell each way in it and
+        // the others two cells straight ahead.
+        for (kind, direction, rows, cols) in [
+            ("glider", "se", 1, 1),
+            ("glider", "sw", 1, -1),
+            ("glider", "ne", -1, 1),
+            ("glider", "nw", -1, -1),
+            ("lwss", "w", 0, -2),
+            ("lwss", "e", 0, 2),
+            ("mwss", "n", -2, 0),
+            ("mwss", "s", 2, 0),
+            ("hwss", "e", 0, 2),
+            ("hwss", "n", -2, 0),
+        ] {
+            let mut universe = empty(40, 40);
+            universe.spawn_ship(kind, 17, 17, direction).unwrap();
+            let start = live(&universe);
+            let (top, bottom, left, right) = universe.occupied_bounds().unwrap();
+            universe.tick_n(4);
+            let shift = |at: u32, by: i32| at.wrapping_add_signed(by);
+            assert_eq!(
+                universe.occupied_bounds(),
+                Some((
+                    shift(top, rows),
+                    shift(bottom, rows),
+                    shift(left, cols),
+                    shift(right, cols)
+                )),
+                "{kind} heading {direction}"
+            );
+            assert_eq!(live(&universe), moved(&start, rows, cols));
+        }
+    }
+
+    #[test]
+    fn thin_boards_tick() {
+        // On a torus a lone cell is its own neighbour all round, eight
+        // times over, and dies of overcrowding.
+        let mut universe = empty(1, 1);
+        universe.toggle_cell(0, 0);
+        assert_eq!(universe.live_neighbor_count(0, 0), 8);
+        universe.tick();
+        assert!(live(&universe).is_empty());
+
+        // On a single row or column it is its own neighbour above and
+        // below, enough to survive, and each cell beside it sees it three
+        // times over and comes to life.
+        let mut row = empty(7, 1);
+        row.toggle_cell(0, 3);
+        row.tick();
+        assert_eq!(live(&row), [(0, 2), (0, 3), (0, 4)]);
+        let mut column = empty(1, 7);
+        column.toggle_cell(3, 0);
+        column.tick();
+        assert_eq!(live(&column), [(2, 0), (3, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn dimensions_are_checked_at_the_limits() {
+        assert_eq!(check_dimensions(1, 1), Ok(1));
+        assert_eq!(check_dimensions(1, 1000), Ok(1000));
+        assert_eq!(check_dimensions(MAX_CELLS, 1), Ok(MAX_CELLS as usize));
+        assert_eq!(check_dimensions(1 << 14, 1 << 14), Ok(MAX_CELLS as usize));
+
+        let too_large = |width, height| {
+            Err(format!(
+                "Universe of {}x{} is too large; it may hold at most {} cells",
+                width, height, MAX_CELLS
+            ))
+        };
+        assert_eq!(
+            check_dimensions(MAX_CELLS + 1, 1),
+            too_large(MAX_CELLS + 1, 1)
+        );
+        assert_eq!(
+            check_dimensions(1 << 14, (1 << 14) + 1),
+            too_large(1 << 14, (1 << 14) + 1)
+        );
+        // The cell count overflows `u32` before it is compared.
+        assert_eq!(
+            check_dimensions(1 << 16, 1 << 16),
+            too_large(1 << 16, 1 << 16)
+        );
+        assert_eq!(
+            check_dimensions(0, 5),
+            Err("Universe must be at least 1x1, not 0x5".to_string())
+        );
+    }
+
+    #[test]
+    fn setting_cells_is_all_or_nothing() {
+        let mut universe = empty(10, 10);
+        universe.set_cells_alive(&[0, 0, 4, 5]).unwrap();
+        let before = universe.cells.clone();
+
+        // The bad pair comes last, after two that would have been set.
+        assert_eq!(
+            universe.set_cells(&[1, 1, 2, 2, 3, 10], ALIVE),
+            Err("Cell (3, 10) at index 4 is outside the 10x10 universe".to_string())
+        );
+        assert_eq!(universe.cells, before);
+        assert_eq!(
+            universe.set_cells(&[0, 0, 4], DEAD),
+            Err("Coordinates must come in (row, col) pairs, but 3 numbers were given".to_string())
+        );
+        assert_eq!(universe.cells, before);
+
+        universe.set_cells_dead(&[0, 0, 4, 5]).unwrap();
+        assert_eq!(live(&universe), []);
+    }
+
+    #[test]
+    fn glider_crashes_into_a_dead_edge() {
+        let mut universe = empty(10, 10);
+        universe.set_boundary("dead").unwrap();
+        universe.set_pattern("glider", 5, 5).unwrap();
+        // It runs into the bottom-right corner and settles there as a
+        // block.
+        universe.tick_n(12);
+        let block = [(8, 8), (8, 9), (9, 8), (9, 9)];
+        assert_eq!(live(&universe), block);
+        universe.tick_n(20);
+        assert_eq!(live(&universe), block);
+    }
+
+    #[test]
+    fn glider_comes_back_round_a_torus() {
+        let mut universe = empty(10, 10);
+        universe.set_pattern("glider", 5, 5).unwrap();
+        // Five cells down and to the right, it has crossed both seams and
+        // is whole again at the top left.
+        universe.tick_n(20);
+        assert_eq!(live(&universe), [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn centering_puts_the_extra_row_and_column_first() {
+        // The gun is 11 rows by 38 columns: 5 rows either side of the
+        // center, and 19 columns to its left against 18 to its right.
+        let mut centered = empty(60, 40);
+        centered
+            .set_pattern_centered("gosper_glider_gun", 20, 30)
+            .unwrap();
+        let mut placed = empty(60, 40);
+        placed.set_pattern("gosper_glider_gun", 15, 11).unwrap();
+        assert_eq!(live(&centered), live(&placed));
+
+        // Near the corner it wraps back round to the far edges.
+        let mut centered = empty(60, 40);
+        centered
+            .set_pattern_centered("gosper_glider_gun", 2, 5)
+            .unwrap();
+        let mut placed = empty(60, 40);
+        placed.set_pattern("gosper_glider_gun", 37, 46).unwrap();
+        assert_eq!(live(&centered), live(&placed));
+        assert!(live(&centered)
+            .iter()
+            .any(|&(row, col)| row < 2 && col > 46));
+    }
+
+    #[test]
+    fn ticks_alternate_between_two_buffers() {
+        // Once packed and once a byte per cell, under a Generations rule.
+        for rule in ["B3/S23", "B3/S23/C3"] {
+            let mut universe = empty(16, 16);
+            universe.set_rule(rule).unwrap();
+            universe.set_pattern("blinker", 6, 6).unwrap();
+            let (front, back) = (universe.cells(), universe.next.as_ptr());
+            let capacity = universe.next.capacity();
+            for tick in 0..20 {
+                let counter = universe.cells_ptr_generation();
+                universe.tick();
+                assert_ne!(universe.cells_ptr_generation(), counter);
+                let expected = if tick % 2 == 0 { back } else { front };
+                assert_eq!(universe.cells(), expected, "{rule}");
+                assert_eq!(universe.next.capacity(), capacity);
+            }
+        }
+    }
+
+    #[test]
+    fn resizing_rebuilds_both_buffers() {
+        let mut universe = empty(16, 16);
+        universe.set_pattern("blinker", 6, 6).unwrap();
+        universe.tick();
+        let counter = universe.cells_ptr_generation();
+        universe.set_width(40).unwrap();
+        assert_ne!(universe.cells_ptr_generation(), counter);
+        assert_eq!(universe.cells_len_bytes(), 40 * 16);
+        assert_eq!(universe.next.len(), universe.cells.len());
+
+        let front = universe.cells();
+        universe.tick();
+        assert_ne!(universe.cells(), front);
+        universe.tick();
+        assert_eq!(universe.cells(), front);
+    }
+}
@@ -0,0 +1,120 @@
+// Life 1.06 pattern support.
+//
+// A `#Life 1.06` header line is followed by one `x y` coordinate pair per
+// live cell, where `x` is the column and `y` the row. Coordinates may be
+// negative.
+
+use wasm_bindgen::prelude::*;
+
+use crate::pattern::Pattern;
+use crate::{PlacementMode, Universe};
+
+const HEADER: &str = "#Life 1.06";
+
+#[wasm_bindgen]
+impl Universe {
+    /// Load a pattern in Life 1.06 format.
+    ///
+    /// The pattern is translated so its bounding box starts at (0, 0) and
+    /// placed in the top-left corner of the universe, and the generation
+    /// counter restarts at 0.
+    pub fn load_life106(&mut self, text: &str) -> Result<(), JsValue> {
+        let pattern = parse(text).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
+        self.place(&pattern, 0, 0, PlacementMode::Replace);
+        self.generation = 0;
+        Ok(())
+    }
+
+    /// Export the live cells in Life 1.06 format, sorted by row then column
+    /// and relative to their bounding box.
+    pub fn to_life106(&self) -> String {
+        encode(&self.live_pattern())
+    }
+}
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        _ => return Err(format!("line 1: expected `{}` header", HEADER)),
+    }
+
+    let mut coords: Vec<(i64, i64)> = Vec::new();
+    for (number, line) in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace().map(str::parse::<i64>);
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => coords.push((y, x)),
+            _ => {
+                return Err(format!(
+                    "line {}: expected an `x y` integer coordinate pair, found `{}`",
+                    number + 1,
+                    line
+                ));
+            }
+        }
+    }
+
+    let min_row = coords.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let min_col = coords.iter().map(|&(_, col)| col).min().unwrap_or(0);
+    let max_row = coords.iter().map(|&(row, _)| row).max().unwrap_or(-1);
+    let max_col = coords.iter().map(|&(_, col)| col).max().unwrap_or(-1);
+
+    let extent = |min: i64, max: i64| {
+        let span = max.checked_sub(min)?.checked_add(1)?;
+        u32::try_from(span).ok()
+    };
+    let (height, width) = match (extent(min_row, max_row), extent(min_col, max_col)) {
+        (Some(height), Some(width)) => (height, width),
+        _ => return Err("pattern coordinates span too large a range".to_string()),
+    };
+
+    let mut cells: Vec<(u32, u32)> = coords
+        .into_iter()
+        .map(|(row, col)| ((row - min_row) as u32, (col - min_col) as u32))
+        .collect();
+    cells.sort_unstable();
+    cells.dedup();
+
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+pub fn encode(pattern: &Pattern) -> String {
+    let mut cells = pattern.cells.clone();
+    cells.sort_unstable();
+
+    let mut out = format!("{}\n", HEADER);
+    for (row, col) in cells {
+        out.push_str(&format!("{} {}\n", col, row));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn coordinates_at_the_ends_of_i64_are_an_error() {
+        let text = format!("#Life 1.06\n{} 0\n{} 0\n", i64::MIN, i64::MAX);
+        assert!(parse(&text).is_err());
+        let text = format!("#Life 1.06\n0 0\n0 {}\n", i64::MAX);
+        assert!(parse(&text).is_err());
+    }
+
+    #[test]
+    fn offsets_are_taken_from_the_bounding_box() {
+        let pattern = parse("#Life 1.06\n-1 -1\n0 -1\n1 0\n").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 2));
+        assert_eq!(pattern.cells, [(0, 0), (0, 1), (1, 2)]);
+    }
+}
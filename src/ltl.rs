@@ -0,0 +1,140 @@
+// Larger-than-Life rules, which count live cells over a square of any radius.
+//
+// A cell's count covers every cell within Chebyshev distance `radius`,
+// including the cell itself as in Golly's `M1` rules. Counts come from
+// prefix sums along each row and then each column, so the cost per tick
+// barely grows with the radius.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::boundary::Boundary;
+use crate::{Universe, ALIVE, DEAD};
+
+const MAX_RADIUS: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LtlRule {
+    pub radius: u32,
+    pub birth_min: u32,
+    pub birth_max: u32,
+    pub survive_min: u32,
+    pub survive_max: u32,
+}
+
+impl LtlRule {
+    // Check the radius is in range and the ranges run from low to high.
+    pub fn check(&self) -> Result<(), String> {
+        if self.radius == 0 || self.radius > MAX_RADIUS {
+            return Err(format!(
+                "Radius must be from 1 to {}, not {}",
+                MAX_RADIUS, self.radius
+            ));
+        }
+        if self.birth_min > self.birth_max || self.survive_min > self.survive_max {
+            return Err(format!(
+                "Invalid ranges: birth {}..{} and survival {}..{} must each run from low to high",
+                self.birth_min, self.birth_max, self.survive_min, self.survive_max
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to a Larger-than-Life rule: a dead cell is born when the live
+    /// cells within `radius` number between `birth_min` and `birth_max`, and
+    /// a live cell survives when they number between `survive_min` and
+    /// `survive_max`. The count includes the cell itself, so Bugs is
+    /// `set_rule_ltl(5, 34, 45, 34, 58)`.
+    pub fn set_rule_ltl(
+        &mut self,
+        radius: u32,
+        birth_min: u32,
+        birth_max: u32,
+        survive_min: u32,
+        survive_max: u32,
+    ) -> Result<(), JsValue> {
+        let rule = LtlRule {
+            radius,
+            birth_min,
+            birth_max,
+            survive_min,
+            survive_max,
+        };
+        rule.check().map_err(|e| JsValue::from_str(&e))?;
+        self.apply_automaton(Automaton::LargerThanLife(rule), 2);
+        Ok(())
+    }
+}
+
+impl Universe {
+    pub(crate) fn tick_ltl(&mut self, rule: LtlRule) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Live cells in the horizontal window around each cell.
+        let mut across = vec![0u32; width * height];
+        for (row, sums) in self.cells.chunks(width).zip(across.chunks_mut(width)) {
+            let live: Vec<u32> = row.iter().map(|&cell| u32::from(cell == ALIVE)).collect();
+            window_sums(&live, rule.radius, self.boundary, sums);
+        }
+
+        // Sum those windows vertically to cover the whole square.
+        let mut counts = vec![0u32; width * height];
+        let mut column = vec![0u32; height];
+        let mut sums = vec![0u32; height];
+        for col in 0..width {
+            for row in 0..height {
+                column[row] = across[row * width + col];
+            }
+            window_sums(&column, rule.radius, self.boundary, &mut sums);
+            for row in 0..height {
+                counts[row * width + col] = sums[row];
+            }
+        }
+
+        let mut next = self.take_next();
+        for ((state, &cell), count) in next.iter_mut().zip(&self.cells).zip(counts) {
+            let (min, max) = if cell == ALIVE {
+                (rule.survive_min, rule.survive_max)
+            } else {
+                (rule.birth_min, rule.birth_max)
+            };
+            *state = if (min..=max).contains(&count) {
+                ALIVE
+            } else {
+                DEAD
+            };
+        }
+        self.swap_in(next);
+    }
+}
+
+// For each position, the sum of `values` within `radius` of it, looking past
+// the ends as the boundary says. On a torus a radius wider than the slice
+// counts cells repeatedly, as a window sliding around it would.
+fn window_sums(values: &[u32], radius: u32, boundary: Boundary, out: &mut [u32]) {
+    let size = values.len() as u32;
+    let radius = i64::from(radius);
+
+    // Prefix sums over the values extended by `radius` past each end.
+    let mut prefix = Vec::with_capacity(values.len() + 2 * radius as usize + 1);
+    let mut total = 0;
+    prefix.push(total);
+    for position in -radius..i64::from(size) + radius {
+        total += boundary
+            .neighbor(position, size)
+            .map_or(0, |p| values[p as usize]);
+        prefix.push(total);
+    }
+
+    let window = 2 * radius as usize + 1;
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = prefix[i + window] - prefix[i];
+    }
+}
@@ -0,0 +1,131 @@
+// MAP rules, which give the next state for every arrangement of a cell and
+// its eight neighbours, as used by Golly.
+//
+// A MAP rulestring is `MAP` followed by 512 bits in base64. Reading a cell's
+// 3x3 neighbourhood row by row from the top left as a 9-bit binary number
+// (so the top-left cell is the most significant bit and the cell itself is
+// bit 4) gives an index into those bits, and the bit at that index, counting
+// from the most significant bit of the first byte, says whether the cell is
+// alive next generation.
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::neighborhood::Neighborhood;
+use crate::rules::Rule;
+use crate::{base64, Universe, ALIVE, DEAD};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapRule {
+    pub table: [u8; 64],
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to a MAP rule, such as Conway's Life written as
+    /// `MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA`.
+    ///
+    /// MAP rules always look at the eight surrounding cells, whatever
+    /// neighbourhood is selected. An invalid string leaves the current rule
+    /// unchanged.
+    pub fn set_rule_map(&mut self, map: &str) -> Result<(), JsValue> {
+        let rule = MapRule::parse(map).map_err(|e| JsValue::from_str(&e))?;
+        self.apply_automaton(Automaton::Map(rule), 2);
+        Ok(())
+    }
+
+    /// The current rule as a MAP rulestring, or `undefined` for rules that
+    /// MAP can't express, such as Generations rules or other automata.
+    pub fn rule_map(&self) -> Option<String> {
+        match self.automaton {
+            Automaton::Map(rule) => Some(rule.to_string()),
+            Automaton::Life if self.rule.states == 2 => {
+                Some(MapRule::from_rule(self.rule, self.neighborhood).to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Universe {
+    pub(crate) fn tick_map(&mut self, rule: MapRule) {
+        let mut next = self.take_next();
+
+        // Positions of the rows or columns either side of a cell, or `None`
+        // past a dead edge.
+        let around = |position: u32, size: u32| {
+            [-1, 0, 1].map(|delta| self.boundary.neighbor(i64::from(position) + delta, size))
+        };
+        for row in 0..self.height {
+            let rows = around(row, self.height);
+            for col in 0..self.width {
+                let cols = around(col, self.width);
+                let mut index = 0;
+                for r in rows {
+                    for c in cols {
+                        let alive = match (r, c) {
+                            (Some(r), Some(c)) => self.cells[self.get_index(r, c)] == ALIVE,
+                            _ => false,
+                        };
+                        index = index << 1 | alive as usize;
+                    }
+                }
+                next[self.get_index(row, col)] = if rule.next(index) { ALIVE } else { DEAD };
+            }
+        }
+
+        self.swap_in(next);
+    }
+}
+
+impl MapRule {
+    pub fn parse(map: &str) -> Result<MapRule, String> {
+        let invalid = |reason: &str| format!("Invalid MAP rule '{}': {}", map.trim(), reason);
+        let data = map
+            .trim()
+            .strip_prefix("MAP")
+            .ok_or_else(|| invalid("expected it to start with MAP"))?;
+        let bytes = base64::decode(data).map_err(|e| invalid(&e))?;
+        let table = <[u8; 64]>::try_from(bytes.as_slice())
+            .map_err(|_| invalid("expected 86 base64 characters after MAP"))?;
+        Ok(MapRule { table })
+    }
+
+    // The MAP equivalent of a two-state Life-like rule, counting the cells
+    // of the given neighbourhood.
+    pub fn from_rule(rule: Rule, neighborhood: Neighborhood) -> MapRule {
+        let mut table = [0u8; 64];
+        for index in 0..512 {
+            let bit = |delta_row: i32, delta_col: i32| {
+                let position = 8 - ((delta_row + 1) * 3 + delta_col + 1);
+                (index >> position & 1) as u8
+            };
+            let live_neighbors = neighborhood
+                .offsets()
+                .iter()
+                .map(|&(delta_row, delta_col)| bit(delta_row, delta_col))
+                .sum();
+            if rule.next(bit(0, 0), live_neighbors) == ALIVE {
+                table[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+        MapRule { table }
+    }
+
+    // Whether a cell whose neighbourhood reads as `index` is alive next.
+    pub fn next(&self, index: usize) -> bool {
+        self.table[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+}
+
+impl fmt::Display for MapRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MAP{}",
+            base64::encode(&self.table).trim_end_matches('=')
+        )
+    }
+}
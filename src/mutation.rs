@@ -0,0 +1,109 @@
+// Exploring the space of Life-like rules by random mutation.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::rules::Rule;
+use crate::Universe;
+
+// The most rules `rule_history` remembers; older entries are dropped first.
+const HISTORY_LIMIT: usize = 1000;
+
+#[wasm_bindgen]
+impl Universe {
+    /// With the given probability, add or remove one neighbour count from the
+    /// current rule's birth or survival set, chosen at random. Returns
+    /// whether the rule changed.
+    ///
+    /// Birth on 0 neighbours is never added or removed unless allowed with
+    /// `set_allow_birth_on_zero`, since it makes the whole universe flash.
+    ///
+    /// Throws an error, changing nothing, unless the universe runs a
+    /// Life-like or Generations rule: Wireworld, Immigration, custom rules
+    /// and the rest have no birth and survival sets to mutate.
+    pub fn mutate_rule(&mut self, probability: f64) -> Result<bool, JsValue> {
+        self.mutate(probability).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Let `mutate_rule` add or remove birth on 0 neighbours. Off by default.
+    pub fn set_allow_birth_on_zero(&mut self, allow: bool) {
+        self.allow_birth_on_zero = allow;
+    }
+
+    /// The rulestrings of every rule applied so far, oldest first, starting
+    /// with the rule the universe was created with.
+    pub fn rule_history(&self) -> Vec<JsValue> {
+        self.rule_history
+            .iter()
+            .map(|rule| JsValue::from_str(&rule.to_string()))
+            .collect()
+    }
+}
+
+impl Universe {
+    fn mutate(&mut self, probability: f64) -> Result<bool, String> {
+        if self.automaton != Automaton::Life || self.custom_rule.is_some() {
+            return Err(
+                "Only Life-like and Generations rules can be mutated; choose one with set_rule first"
+                    .to_string(),
+            );
+        }
+        if self.rng.next_f64() >= probability {
+            return Ok(false);
+        }
+
+        // Bits 0 to 8 are the birth counts and 9 to 17 the survival counts.
+        let first = if self.allow_birth_on_zero { 0 } else { 1 };
+        let choices = 18 - first;
+        let choice = first + (self.rng.next_f64() * f64::from(choices)) as u32;
+
+        let mut rule = self.rule;
+        if choice < 9 {
+            rule.birth ^= 1 << choice;
+        } else {
+            rule.survival ^= 1 << (choice - 9);
+        }
+        self.apply_rule(rule);
+        Ok(true)
+    }
+
+    pub(crate) fn record_rule(&mut self, rule: Rule) {
+        if self.rule_history.len() == HISTORY_LIMIT {
+            self.rule_history.remove(0);
+        }
+        self.rule_history.push(rule);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::Automaton;
+    use crate::testing::soup;
+
+    #[test]
+    fn mutation_keeps_the_automaton() {
+        let mut universe = soup(20, 20, 0.3, 19);
+        universe.set_automaton("brians_brain").unwrap();
+        universe.tick_n(3);
+        assert_eq!(universe.mutate(1.0), Ok(true));
+        assert_eq!(universe.automaton, Automaton::Life);
+        assert_eq!(universe.rule.states, 3);
+        assert!(universe.cells.contains(&2));
+    }
+
+    #[test]
+    fn other_automata_are_not_mutated() {
+        for automaton in ["wireworld", "immigration"] {
+            let mut universe = soup(20, 20, 0.3, 20);
+            universe.set_automaton(automaton).unwrap();
+            universe.tick_n(3);
+            let before = (universe.automaton, universe.rule, universe.cells.clone());
+            assert!(universe.mutate(1.0).is_err());
+            assert_eq!((universe.automaton, universe.rule, universe.cells), before);
+        }
+        let mut universe = soup(20, 20, 0.3, 20);
+        universe.set_elementary_rule(30);
+        assert!(universe.mutate(1.0).is_err());
+        assert_eq!(universe.automaton, Automaton::Elementary(30));
+    }
+}
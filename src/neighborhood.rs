@@ -0,0 +1,88 @@
+// The cells counted as a cell's neighbours.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+// The eight surrounding cells.
+const MOORE: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+// The four orthogonally adjacent cells.
+const VON_NEUMANN: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    Moore,
+    VonNeumann,
+}
+
+impl Neighborhood {
+    // (row, column) offsets of the neighbours from the cell itself.
+    pub fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::VonNeumann => &VON_NEUMANN,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Choose which cells count as neighbours: `"moore"` for the eight
+    /// surrounding cells (the default) or `"von_neumann"` for the four
+    /// orthogonally adjacent ones. Every automaton that counts neighbours
+    /// uses it.
+    pub fn set_neighborhood(&mut self, kind: &str) -> Result<(), JsValue> {
+        self.neighborhood = match kind.trim().to_lowercase().as_str() {
+            "moore" => Neighborhood::Moore,
+            "von_neumann" => Neighborhood::VonNeumann,
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Unknown neighborhood '{}'; expected 'moore' or 'von_neumann'",
+                    kind.trim()
+                )))
+            }
+        };
+        Ok(())
+    }
+}
+
+impl Universe {
+    // Indices of a cell's neighbours, leaving out any past a dead edge.
+    pub(crate) fn neighbor_indices(
+        &self,
+        row: u32,
+        column: u32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        // Away from the edges every neighbour is a fixed step from the cell,
+        // so only cells on the border need the boundary's wrapping.
+        let interior = row > 0 && row + 1 < self.height && column > 0 && column + 1 < self.width;
+        let idx = self.get_index(row, column) as isize;
+        let width = self.width as isize;
+
+        self.neighborhood
+            .offsets()
+            .iter()
+            .filter_map(move |&(delta_row, delta_col)| {
+                if interior {
+                    return Some((idx + delta_row as isize * width + delta_col as isize) as usize);
+                }
+                let r = self
+                    .boundary
+                    .neighbor(i64::from(row) + i64::from(delta_row), self.height)?;
+                let c = self
+                    .boundary
+                    .neighbor(i64::from(column) + i64::from(delta_col), self.width)?;
+                Some(self.get_index(r, c))
+            })
+    }
+}
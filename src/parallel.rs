@@ -0,0 +1,60 @@
+// Running the per-cell loop on several threads.
+//
+// Each band of tiles one tile high goes to its own task. Only Life-like
+// rules run in parallel: they need nothing but the cells and neighbour
+// counts, while other automata reach into the universe in ways that can't
+// be shared between threads.
+
+use rayon::prelude::*;
+
+use crate::tiles::TILE_SIZE;
+use crate::{Universe, ALIVE};
+
+// Start the thread pool that ticks run on, as `initThreadPool(threads)`
+// from JavaScript. It returns a promise that must resolve before the first
+// tick.
+#[cfg(target_arch = "wasm32")]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+impl Universe {
+    // Like `step_tiles`, for a Life-like rule, splitting the rows across
+    // the thread pool. Two-state rules step packed instead, so this runs
+    // Generations rules.
+    pub(crate) fn step_life_parallel(&self, next: &mut [u8], changed: &mut Vec<u32>) {
+        let width = self.width as usize;
+        let band = TILE_SIZE as usize * width;
+        let across = self.width.div_ceil(TILE_SIZE) as usize;
+        let cells = &self.cells;
+        let counts = &self.neighbor_counts.counts;
+        let active = &self.tiles.active;
+        let rule = self.rule;
+
+        // Each band lists the cells it changed, to be joined in order.
+        let bands: Vec<Vec<u32>> = next
+            .par_chunks_mut(band)
+            .enumerate()
+            .map(|(tile_row, out)| {
+                let mut changed = Vec::new();
+                let start = tile_row * band;
+                for tile_col in (0..across).filter(|&c| active[tile_row * across + c]) {
+                    let left = tile_col * TILE_SIZE as usize;
+                    let right = (left + TILE_SIZE as usize).min(width);
+                    for (offset, row) in out.chunks_mut(width).enumerate() {
+                        let first = start + offset * width;
+                        for (col, state) in row[left..right].iter_mut().enumerate() {
+                            let idx = first + left + col;
+                            *state = rule.next(cells[idx], counts[idx]);
+                            if (*state == ALIVE) != (cells[idx] == ALIVE) {
+                                changed.push(idx as u32);
+                            }
+                        }
+                    }
+                }
+                changed
+            })
+            .collect();
+        for band in bands {
+            changed.extend(band);
+        }
+    }
+}
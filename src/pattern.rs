@@ -0,0 +1,399 @@
+// A pattern, either built in or decoded from one of the supported text
+// formats.
+//
+// Live cells are stored as (row, column) offsets from the top-left corner of
+// the pattern's bounding box.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub width: u32,
+    pub height: u32,
+    pub cells: Vec<(u32, u32)>,
+}
+
+impl Pattern {
+    // Mirror the pattern (left to right for `flip_h`, top to bottom for
+    // `flip_v`) and then rotate it clockwise by the given number of quarter
+    // turns. The bounding box is transformed along with the cells.
+    pub fn transformed(&self, quarter_turns: u8, flip_h: bool, flip_v: bool) -> Pattern {
+        let mut result = self.clone();
+        for cell in &mut result.cells {
+            if flip_h {
+                cell.1 = self.width - 1 - cell.1;
+            }
+            if flip_v {
+                cell.0 = self.height - 1 - cell.0;
+            }
+        }
+        for _ in 0..quarter_turns % 4 {
+            for cell in &mut result.cells {
+                *cell = (cell.1, result.height - 1 - cell.0);
+            }
+            std::mem::swap(&mut result.width, &mut result.height);
+        }
+        result
+    }
+}
+
+// What kind of object a pattern is, for grouping patterns in a UI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    // A small pattern that runs for a long time before stabilizing, after
+    // `lifespan` generations.
+    Methuselah { lifespan: u32 },
+    Gun,
+    // Registered at runtime with `Universe::register_pattern`.
+    Custom,
+}
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::StillLife => "still_life",
+            Category::Oscillator => "oscillator",
+            Category::Spaceship => "spaceship",
+            Category::Methuselah { .. } => "methuselah",
+            Category::Gun => "gun",
+            Category::Custom => "custom",
+        }
+    }
+
+    pub fn lifespan(self) -> Option<u32> {
+        match self {
+            Category::Methuselah { lifespan } => Some(lifespan),
+            _ => None,
+        }
+    }
+}
+
+// An entry in the built-in pattern library.
+struct PatternDef {
+    name: &'static str,
+    width: u32,
+    height: u32,
+    category: Category,
+    cells: &'static [(u32, u32)],
+}
+
+// Patterns built into the library, in the order they are listed to users.
+#[rustfmt::skip]
+static LIBRARY: &[PatternDef] = &[
+    // Still lifes
+    PatternDef {
+        name: "block",
+        width: 2,
+        height: 2,
+        category: Category::StillLife,
+        cells: &[
+            (0, 0), (0, 1),
+            (1, 0), (1, 1),
+        ],
+    },
+    PatternDef {
+        name: "beehive",
+        width: 4,
+        height: 3,
+        category: Category::StillLife,
+        cells: &[
+            (0, 1), (0, 2),
+            (1, 0), (1, 3),
+            (2, 1), (2, 2),
+        ],
+    },
+    PatternDef {
+        name: "loaf",
+        width: 4,
+        height: 4,
+        category: Category::StillLife,
+        cells: &[
+            (0, 1), (0, 2),
+            (1, 0), (1, 3),
+            (2, 1), (2, 3),
+            (3, 2),
+        ],
+    },
+    // Oscillators
+    PatternDef {
+        name: "blinker",
+        width: 3,
+        height: 1,
+        category: Category::Oscillator,
+        cells: &[
+            (0, 0), (0, 1), (0, 2),
+        ],
+    },
+    PatternDef {
+        name: "toad",
+        width: 4,
+        height: 2,
+        category: Category::Oscillator,
+        cells: &[
+            (0, 1), (0, 2), (0, 3),
+            (1, 0), (1, 1), (1, 2),
+        ],
+    },
+    PatternDef {
+        name: "beacon",
+        width: 4,
+        height: 4,
+        category: Category::Oscillator,
+        cells: &[
+            (0, 0), (0, 1),
+            (1, 0), (1, 1),
+            (2, 2), (2, 3),
+            (3, 2), (3, 3),
+        ],
+    },
+    PatternDef {
+        name: "pulsar",
+        width: 17,
+        height: 17,
+        category: Category::Oscillator,
+        cells: &[
+            // Top part
+            (2, 4), (2, 5), (2, 6), (2, 10), (2, 11), (2, 12),
+            (4, 2), (4, 7), (4, 9), (4, 14),
+            (5, 2), (5, 7), (5, 9), (5, 14),
+            (6, 2), (6, 7), (6, 9), (6, 14),
+            (7, 4), (7, 5), (7, 6), (7, 10), (7, 11), (7, 12),
+            // Bottom part
+            (9, 4), (9, 5), (9, 6), (9, 10), (9, 11), (9, 12),
+            (10, 2), (10, 7), (10, 9), (10, 14),
+            (11, 2), (11, 7), (11, 9), (11, 14),
+            (12, 2), (12, 7), (12, 9), (12, 14),
+            (14, 4), (14, 5), (14, 6), (14, 10), (14, 11), (14, 12),
+        ],
+    },
+    PatternDef {
+        name: "pentadecathlon",
+        width: 10,
+        height: 3,
+        category: Category::Oscillator,
+        cells: &[
+            (0, 2), (0, 7),
+            (1, 0), (1, 1), (1, 3), (1, 4), (1, 5), (1, 6), (1, 8), (1, 9),
+            (2, 2), (2, 7),
+        ],
+    },
+    // Spaceships
+    PatternDef {
+        name: "glider",
+        width: 3,
+        height: 3,
+        category: Category::Spaceship,
+        cells: &[
+            (0, 1), (1, 2), (2, 0), (2, 1), (2, 2),
+        ],
+    },
+    PatternDef {
+        name: "lwss",
+        width: 5,
+        height: 4,
+        category: Category::Spaceship,
+        cells: &[
+            (0, 1), (0, 4),
+            (1, 0),
+            (2, 0), (2, 4),
+            (3, 0), (3, 1), (3, 2), (3, 3),
+        ],
+    },
+    PatternDef {
+        name: "mwss",
+        width: 6,
+        height: 5,
+        category: Category::Spaceship,
+        cells: &[
+            (0, 3),
+            (1, 1), (1, 5),
+            (2, 0),
+            (3, 0), (3, 5),
+            (4, 0), (4, 1), (4, 2), (4, 3), (4, 4),
+        ],
+    },
+    PatternDef {
+        name: "hwss",
+        width: 7,
+        height: 5,
+        category: Category::Spaceship,
+        cells: &[
+            (0, 3), (0, 4),
+            (1, 1), (1, 6),
+            (2, 0),
+            (3, 0), (3, 6),
+            (4, 0), (4, 1), (4, 2), (4, 3), (4, 4), (4, 5),
+        ],
+    },
+    // Methuselahs
+    PatternDef {
+        name: "r_pentomino",
+        width: 3,
+        height: 3,
+        category: Category::Methuselah { lifespan: 1103 },
+        cells: &[
+            (0, 1), (0, 2),
+            (1, 0), (1, 1),
+            (2, 1),
+        ],
+    },
+    PatternDef {
+        name: "acorn",
+        width: 7,
+        height: 3,
+        category: Category::Methuselah { lifespan: 5206 },
+        cells: &[
+            (0, 1),
+            (1, 3),
+            (2, 0), (2, 1), (2, 4), (2, 5), (2, 6),
+        ],
+    },
+    PatternDef {
+        name: "diehard",
+        width: 8,
+        height: 3,
+        category: Category::Methuselah { lifespan: 130 },
+        cells: &[
+            (0, 6),
+            (1, 0), (1, 1),
+            (2, 1), (2, 5), (2, 6), (2, 7),
+        ],
+    },
+    PatternDef {
+        name: "bunnies",
+        width: 8,
+        height: 4,
+        category: Category::Methuselah { lifespan: 17332 },
+        cells: &[
+            (0, 0), (0, 6),
+            (1, 2), (1, 6),
+            (2, 2), (2, 5), (2, 7),
+            (3, 1), (3, 3),
+        ],
+    },
+    PatternDef {
+        name: "rabbits",
+        width: 7,
+        height: 3,
+        category: Category::Methuselah { lifespan: 17331 },
+        cells: &[
+            (0, 0), (0, 4), (0, 5), (0, 6),
+            (1, 0), (1, 1), (1, 2), (1, 5),
+            (2, 1),
+        ],
+    },
+    // Guns
+    PatternDef {
+        name: "gosper_glider_gun",
+        width: 38,
+        height: 11,
+        category: Category::Gun,
+        cells: &[
+            (1, 25),
+            (2, 23), (2, 25),
+            (3, 13), (3, 14), (3, 21), (3, 22), (3, 35), (3, 36),
+            (4, 12), (4, 16), (4, 21), (4, 22), (4, 35), (4, 36),
+            (5, 1), (5, 2), (5, 11), (5, 17), (5, 21), (5, 22),
+            (6, 1), (6, 2), (6, 11), (6, 15), (6, 17), (6, 18), (6, 23), (6, 25),
+            (7, 11), (7, 17), (7, 25),
+            (8, 12), (8, 16),
+            (9, 13), (9, 14),
+        ],
+    },
+    PatternDef {
+        name: "simkin_glider_gun",
+        width: 33,
+        height: 21,
+        category: Category::Gun,
+        cells: &[
+            (0, 0), (0, 1), (0, 7), (0, 8),
+            (1, 0), (1, 1), (1, 7), (1, 8),
+            (3, 4), (3, 5),
+            (4, 4), (4, 5),
+            (9, 22), (9, 23), (9, 25), (9, 26),
+            (10, 21), (10, 27),
+            (11, 21), (11, 28), (11, 31), (11, 32),
+            (12, 21), (12, 22), (12, 23), (12, 27), (12, 31), (12, 32),
+            (13, 26),
+            (17, 20), (17, 21),
+            (18, 20),
+            (19, 21), (19, 22), (19, 23),
+            (20, 23),
+        ],
+    },
+];
+
+// Look up one of the patterns built into the library by name.
+pub fn builtin(name: &str) -> Option<Pattern> {
+    LIBRARY
+        .iter()
+        .find(|def| same_name(def.name, name))
+        .map(|def| Pattern {
+            width: def.width,
+            height: def.height,
+            cells: def.cells.to_vec(),
+        })
+}
+
+// Category of a built-in pattern.
+pub fn builtin_category(name: &str) -> Option<Category> {
+    LIBRARY
+        .iter()
+        .find(|def| same_name(def.name, name))
+        .map(|def| def.category)
+}
+
+// Names of all built-in patterns.
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    LIBRARY.iter().map(|def| def.name)
+}
+
+// Pattern names are matched case-insensitively, ignoring surrounding
+// whitespace.
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+// An error message for a pattern name that isn't among `known`, suggesting
+// the closest one.
+pub fn unknown_name(name: &str, known: &[String]) -> String {
+    match closest_name(name, known) {
+        Some(suggestion) => format!(
+            "Unknown pattern '{}'; did you mean '{}'?",
+            name.trim(),
+            suggestion
+        ),
+        None => format!(
+            "Unknown pattern '{}'; available patterns: {}",
+            name.trim(),
+            known.join(", ")
+        ),
+    }
+}
+
+// The known name closest to `name` by edit distance, if any is close enough
+// to plausibly be a typo.
+pub fn closest_name<'a>(name: &str, known: &'a [String]) -> Option<&'a str> {
+    let name = name.trim().to_lowercase();
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|&(distance, candidate)| distance <= candidate.len().max(4) / 3)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,266 @@
+// Hashing the state of the board, to spot when it repeats.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::tiles::StepConfig;
+use crate::{platform, Timer, Universe, DEAD};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// The board as it was after each recent generation, kept once
+// `detect_period` has been called. Whole states are kept alongside their
+// hashes so a repeat is confirmed cell by cell rather than trusting the
+// hash.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    // How many generations to keep; nothing is recorded while this is 0.
+    capacity: usize,
+    // The settings the recorded generations ran under.
+    config: Option<StepConfig>,
+    // Oldest first.
+    hashes: VecDeque<u64>,
+    states: VecDeque<Vec<u8>>,
+}
+
+impl History {
+    fn clear(&mut self) {
+        self.hashes.clear();
+        self.states.clear();
+    }
+
+    // Keep `capacity` generations from now on, dropping the oldest if there
+    // are more.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let excess = self.states.len().saturating_sub(capacity);
+        self.hashes.drain(..excess);
+        self.states.drain(..excess);
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// A 64-bit FNV-1a hash of the dimensions and every cell's state, in
+    /// row-major order. Equal boards always hash alike, so a repeated hash
+    /// means the board has very likely entered a cycle.
+    ///
+    /// The hash depends only on what the board holds, not on how it is
+    /// stored, and boards of swapped dimensions hash differently.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash = FNV_OFFSET;
+        let mut feed = |byte: u8| hash = (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        for byte in self
+            .width
+            .to_le_bytes()
+            .into_iter()
+            .chain(self.height.to_le_bytes())
+        {
+            feed(byte);
+        }
+        for idx in 0..self.cells.len() {
+            feed(self.state(idx));
+        }
+        hash
+    }
+
+    /// The period of the cycle the board is in, if it has repeated within
+    /// the last `max_period` generations: 1 for a still life, 0 if no repeat
+    /// has been seen, or -1 if every cell is dead.
+    ///
+    /// The first call starts recording the board after every generation, so
+    /// a cycle is only found once it has gone round since then; editing the
+    /// board or changing the rule starts the record again. A copy of the
+    /// board is kept for each of the last `max_period` generations, or more
+    /// if the settled callback looks further back, until the next call asks
+    /// for a different number, so keep it small on large boards. Call
+    /// `stop_detecting_periods` to stop recording.
+    pub fn detect_period(&mut self, max_period: u32) -> i32 {
+        if self.is_extinct() {
+            return -1;
+        }
+        #[cfg(feature = "web")]
+        let max_period = max_period.max(self.settled_max_period());
+        self.history.set_capacity(max_period as usize + 1);
+
+        if !self.history_is_current() {
+            self.history.clear();
+            self.record_history();
+            return 0;
+        }
+
+        let history = &self.history;
+        let newest = history.states.len() - 1;
+        let hash = history.hashes[newest];
+        (1..=newest.min(max_period as usize))
+            .find(|&period| {
+                history.hashes[newest - period] == hash
+                    && history.states[newest - period] == self.cells
+            })
+            .map_or(0, |period| period as i32)
+    }
+
+    /// Tick until the board repeats with a period of at most `max_period`
+    /// or dies out, as found by `detect_period`, running at most
+    /// `max_generations` generations. Returns how many generations ran,
+    /// which is where the cycle began plus one period, since the repeat
+    /// has to be seen. Each generation is reported, saved and autosaved just
+    /// as those run by `tick_n` are.
+    ///
+    /// Throws an error if the board is still changing after
+    /// `max_generations`, leaving it at that generation.
+    pub fn tick_until_stable(
+        &mut self,
+        max_generations: u32,
+        max_period: u32,
+    ) -> Result<u32, JsValue> {
+        let _timer = self
+            .profiling
+            .then(|| Timer::new("Universe::tick_until_stable"));
+        let started = platform::now();
+
+        #[cfg(feature = "web")]
+        self.watch_settled();
+        self.remember();
+        let mut ran = 0;
+        let mut period = self.detect_period(max_period);
+        while period == 0 && ran < max_generations {
+            self.run_generation();
+            ran += 1;
+            period = self.detect_period(max_period);
+        }
+        self.finish_timing(started, ran);
+        #[cfg(feature = "web")]
+        {
+            self.autosave_after(ran);
+            self.after_ticks();
+        }
+
+        if period == 0 {
+            return Err(JsValue::from_str(&format!(
+                "Still changing after {} generations",
+                max_generations
+            )));
+        }
+        Ok(ran)
+    }
+
+    /// Stop recording the board for `detect_period` and let the record go.
+    /// The next call to `detect_period`, `is_stable` or `tick_until_stable`,
+    /// or the next generation with a settled callback set, starts it again.
+    pub fn stop_detecting_periods(&mut self) {
+        self.history = History::default();
+    }
+
+    /// Whether every cell is dead and no ants are walking, so nothing more
+    /// can happen.
+    pub fn is_extinct(&self) -> bool {
+        self.ants.is_empty() && self.cells.iter().all(|&cell| cell == DEAD)
+    }
+
+    /// Whether the board is a still life, or extinct. Like `detect_period`,
+    /// this only knows once a generation has passed since the first call.
+    pub fn is_stable(&mut self) -> bool {
+        matches!(self.detect_period(1), -1 | 1)
+    }
+}
+
+impl Universe {
+    // Whether the newest record is the board as it is now, under the same
+    // settings; otherwise it was edited since, and the record no longer
+    // shows how it got here.
+    pub(crate) fn history_is_current(&self) -> bool {
+        self.history.config == Some(self.step_config())
+            && self.history.states.back() == Some(&self.cells)
+    }
+
+    // Forget the recorded generations, keeping the record going.
+    pub(crate) fn forget_history(&mut self) {
+        self.history.clear();
+    }
+
+    // Remember the board after a generation, if `detect_period` wants it.
+    pub(crate) fn record_history(&mut self) {
+        if self.history.capacity == 0 {
+            return;
+        }
+        let config = self.step_config();
+        if self.history.config != Some(config) {
+            self.history.clear();
+            self.history.config = Some(config);
+        }
+
+        let hash = self.state_hash();
+        let history = &mut self.history;
+        // Reuse the oldest copy's buffer once the record is full.
+        let mut state = if history.states.len() >= history.capacity {
+            history.hashes.pop_front();
+            history.states.pop_front().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        state.clone_from(&self.cells);
+        history.hashes.push_back(hash);
+        history.states.push_back(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pattern::builtin_category;
+    use crate::testing::{empty, soup};
+
+    #[test]
+    fn r_pentomino_settles_after_its_lifespan() {
+        // Far enough from the edges that the gliders it throws off only
+        // turn into blocks against them long before the rest settles.
+        let mut universe = empty(160, 120);
+        universe.set_boundary("dead").unwrap();
+        universe.set_pattern("r_pentomino", 60, 80).unwrap();
+        let lifespan = builtin_category("r_pentomino")
+            .and_then(|category| category.lifespan())
+            .unwrap();
+        assert_eq!(lifespan, 1103);
+
+        // The cycle has to go round once to be seen.
+        assert_eq!(universe.tick_until_stable(2000, 2).unwrap(), lifespan + 2);
+        assert_eq!(universe.detect_period(2), 2);
+        assert_eq!(universe.generation(), u64::from(lifespan) + 2);
+    }
+
+    #[test]
+    fn rolling_back_starts_the_record_afresh() {
+        let mut universe = soup(40, 30, 0.3, 17);
+        universe.enable_checkpoints(10, 5);
+        assert_eq!(universe.detect_period(10), 0);
+        universe.tick_n(30);
+        assert_eq!(universe.detect_period(10), 0);
+
+        // Generation 25 was recorded on the way to 30, but the record
+        // doesn't show a cycle back to it.
+        universe.rollback_to(25).unwrap();
+        assert_eq!(universe.detect_period(10), 0);
+    }
+
+    #[test]
+    fn the_record_keeps_only_what_was_last_asked_for() {
+        let mut universe = soup(40, 30, 0.3, 18);
+        universe.detect_period(50);
+        universe.tick_n(60);
+        assert_eq!(universe.history.states.len(), 51);
+
+        assert!(!universe.is_stable());
+        assert_eq!(universe.history.states.len(), 2);
+        universe.tick_n(10);
+        assert_eq!(universe.history.states.len(), 2);
+
+        universe.stop_detecting_periods();
+        universe.tick_n(10);
+        assert!(universe.history.states.is_empty());
+        assert_eq!(universe.detect_period(3), 0);
+        universe.tick_n(10);
+        assert_eq!(universe.history.states.len(), 4);
+    }
+}
@@ -0,0 +1,93 @@
+// Plaintext (`.cells`) pattern support.
+//
+// Lines starting with `!` are comments, `.` is a dead cell and `O` is a live
+// cell. Lines may be ragged; missing trailing characters are dead cells.
+
+use wasm_bindgen::prelude::*;
+
+use crate::pattern::Pattern;
+use crate::{PlacementMode, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Load a pattern in plaintext `.cells` format with its top-left corner
+    /// at the given position, wrapping at the edges like `set_pattern`. The
+    /// generation counter restarts at 0.
+    pub fn load_plaintext(&mut self, text: &str, row: u32, col: u32) -> Result<(), JsValue> {
+        let pattern = parse(text).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
+        self.place(&pattern, row, col, PlacementMode::Replace);
+        self.generation = 0;
+        Ok(())
+    }
+
+    /// Export the live cells in plaintext `.cells` format, trimmed to their
+    /// bounding box.
+    pub fn to_plaintext(&self) -> String {
+        encode(&self.live_pattern())
+    }
+
+    /// Copy a rectangle of cells, wrapping at the edges, as a plaintext
+    /// `.cells` string that can be passed to `paste_region`.
+    pub fn copy_region(&self, row: u32, col: u32, height: u32, width: u32) -> String {
+        encode(&self.region_pattern(row, col, height, width))
+    }
+
+    /// Paste a region produced by `copy_region` with its top-left corner at
+    /// the given position. The whole rectangle is overwritten, wrapping at
+    /// the edges like `set_pattern`.
+    pub fn paste_region(&mut self, text: &str, row: u32, col: u32) -> Result<(), JsValue> {
+        self.load_plaintext(text, row, col)
+    }
+}
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut cells = Vec::new();
+    let mut width = 0;
+    let mut height = 0;
+
+    let rows = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with('!'));
+    for (row, (number, line)) in rows.enumerate() {
+        let line = line.trim_end();
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => {}
+                'O' => cells.push((row as u32, col as u32)),
+                other => {
+                    return Err(format!(
+                        "line {}: unexpected character '{}', expected '.' or 'O'",
+                        number + 1,
+                        other
+                    ));
+                }
+            }
+        }
+        if !line.is_empty() {
+            width = width.max(line.chars().count() as u32);
+            height = row as u32 + 1;
+        }
+    }
+
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+pub fn encode(pattern: &Pattern) -> String {
+    let mut grid = vec![vec!['.'; pattern.width as usize]; pattern.height as usize];
+    for &(row, col) in &pattern.cells {
+        grid[row as usize][col as usize] = 'O';
+    }
+
+    let mut out = String::new();
+    for line in grid {
+        out.extend(line);
+        out.push('\n');
+    }
+    out
+}
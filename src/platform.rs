@@ -0,0 +1,58 @@
+// The few things the simulation needs from its host: logging, random seeds
+// and a clock.
+//
+// With the `web` feature, which is on by default, these come from the
+// browser when built for wasm. Without it, or when built for the host, they
+// come from the standard library and `rand`, so the crate can be built,
+// tested and benchmarked natively.
+
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+use web_sys::console;
+
+/// Write a line to the browser console, or to standard output natively.
+#[allow(dead_code)]
+pub fn log(message: &str) {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    console::log_1(&message.into());
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    println!("{}", message);
+}
+
+/// A random seed, from `crypto.getRandomValues` in a browser, falling back
+/// on `Math.random` where that isn't available, or from `rand` natively.
+pub fn random_seed() -> u64 {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    {
+        use wasm_bindgen::JsCast;
+
+        let crypto = js_sys::Reflect::get(&js_sys::global(), &"crypto".into())
+            .ok()
+            .filter(|crypto| crypto.is_object())
+            .map(|crypto| crypto.unchecked_into::<web_sys::Crypto>());
+        let mut bytes = [0; 8];
+        if crypto.is_some_and(|crypto| crypto.get_random_values_with_u8_array(&mut bytes).is_ok()) {
+            return u64::from_le_bytes(bytes);
+        }
+        let half = || (js_sys::Math::random() * 4_294_967_296.0) as u64;
+        (half() << 32) | half()
+    }
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    return rand::random();
+}
+
+/// The current time in milliseconds, from `performance.now()` in a browser
+/// or a monotonic clock natively.
+pub fn now() -> f64 {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    return web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or_else(js_sys::Date::now, |performance| performance.now());
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
@@ -0,0 +1,145 @@
+// Counting live cells, and how many were born, died and survived in the
+// last tick.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{heatmap, Universe, ALIVE};
+
+// The cells that changed in one generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub births: u32,
+    pub deaths: u32,
+    pub survivals: u32,
+}
+
+// The population after each recent generation, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct PopulationHistory {
+    // Nothing is recorded while this is 0.
+    capacity: usize,
+    samples: VecDeque<u32>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// The number of live cells; dying cells aren't counted.
+    pub fn population(&self) -> u32 {
+        self.cells.iter().filter(|&&cell| cell == ALIVE).count() as u32
+    }
+
+    /// How many cells came to life in the last generation run by `tick`,
+    /// `tick_n` or `tick_delta`. Zero after `clear` or `randomize`.
+    pub fn last_tick_births(&self) -> u32 {
+        self.changes.births
+    }
+
+    /// How many live cells stopped being alive in the last generation.
+    pub fn last_tick_deaths(&self) -> u32 {
+        self.changes.deaths
+    }
+
+    /// How many cells were alive both before and after the last generation.
+    pub fn last_tick_survivals(&self) -> u32 {
+        self.changes.survivals
+    }
+
+    /// Record the population after every generation, keeping the latest
+    /// `capacity` and dropping the oldest first. Every generation a
+    /// `tick_n` runs is recorded, not just the last. 0, the default, stops
+    /// recording and forgets the samples.
+    pub fn enable_population_history(&mut self, capacity: u32) {
+        let history = &mut self.population_history;
+        history.capacity = capacity as usize;
+        while history.samples.len() > history.capacity {
+            history.samples.pop_front();
+        }
+    }
+
+    /// The recorded populations, oldest first.
+    pub fn population_history(&self) -> js_sys::Uint32Array {
+        let samples: Vec<u32> = self.population_history.samples.iter().copied().collect();
+        js_sys::Uint32Array::from(&samples[..])
+    }
+
+    /// Forget the recorded populations, leaving recording on.
+    pub fn clear_population_history(&mut self) {
+        self.population_history.samples.clear();
+    }
+}
+
+impl Universe {
+    // Advance one generation like `step`, counting its births, deaths and
+    // survivals.
+    pub(crate) fn step_counted(&mut self) {
+        // Ants flip cells before the rule runs, so the spare buffer won't
+        // hold the cells as they were; keep a copy instead.
+        let walking = !self.ants.is_empty();
+        if walking {
+            self.delta_base.clone_from(&self.cells);
+        }
+        let front = self.cells.as_ptr();
+
+        self.sync_ages();
+        self.fit_heatmap();
+        self.fit_trail();
+        self.step();
+        self.advance_ages();
+
+        // Without ants, a step that didn't swap buffers changed nothing.
+        let before = if walking {
+            &self.delta_base
+        } else if self.cells.as_ptr() != front {
+            &self.next
+        } else {
+            &self.cells
+        };
+        let mut changes = Changes::default();
+        for (&now, &was) in self.cells.iter().zip(before) {
+            match (was == ALIVE, now == ALIVE) {
+                (false, true) => changes.births += 1,
+                (true, false) => changes.deaths += 1,
+                (true, true) => changes.survivals += 1,
+                (false, false) => {}
+            }
+        }
+        self.changes = changes;
+        let history = &mut self.population_history;
+        if history.capacity > 0 {
+            if history.samples.len() == history.capacity {
+                history.samples.pop_front();
+            }
+            history
+                .samples
+                .push_back(changes.births + changes.survivals);
+        }
+        if let Some(heat) = &mut self.heatmap {
+            heatmap::record(heat, before, &self.cells);
+        }
+        self.trail.record(before, &self.cells);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::soup;
+
+    #[test]
+    fn rolling_back_records_nothing_more() {
+        let mut universe = soup(40, 30, 0.3, 16);
+        universe.enable_population_history(100);
+        universe.enable_checkpoints(10, 5);
+        let mut populations = Vec::new();
+        for _ in 0..30 {
+            universe.tick();
+            populations.push(universe.population());
+        }
+        assert_eq!(universe.population_history.samples, populations);
+
+        universe.rollback_to(25).unwrap();
+        assert_eq!(universe.population(), populations[24]);
+        assert_eq!(universe.population_history.samples, populations);
+    }
+}
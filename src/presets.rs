@@ -0,0 +1,65 @@
+// Well-known Life-like rules, so callers can pick one without remembering its
+// rulestring.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::Universe;
+
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RulePreset {
+    Conway = 0,
+    HighLife = 1,
+    Seeds = 2,
+    DayAndNight = 3,
+    LifeWithoutDeath = 4,
+    Maze = 5,
+    Anneal = 6,
+    Replicator = 7,
+}
+
+impl RulePreset {
+    pub fn rulestring(self) -> &'static str {
+        match self {
+            RulePreset::Conway => "B3/S23",
+            RulePreset::HighLife => "B36/S23",
+            RulePreset::Seeds => "B2/S",
+            RulePreset::DayAndNight => "B3678/S34678",
+            RulePreset::LifeWithoutDeath => "B3/S012345678",
+            RulePreset::Maze => "B3/S12345",
+            RulePreset::Anneal => "B4678/S35678",
+            RulePreset::Replicator => "B1357/S1357",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            RulePreset::Conway => "Conway's Game of Life",
+            RulePreset::HighLife => "Like Life, with a small self-replicating pattern",
+            RulePreset::Seeds => "Every live cell dies each generation, so patterns explode",
+            RulePreset::DayAndNight => "Live and dead cells behave symmetrically",
+            RulePreset::LifeWithoutDeath => "Cells never die, so patterns grow into ladders",
+            RulePreset::Maze => "Grows into maze-like corridors",
+            RulePreset::Anneal => "Regions of live and dead cells smooth out like a cooling metal",
+            RulePreset::Replicator => "Every pattern is eventually replaced by copies of itself",
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to one of the preset rules.
+    pub fn apply_preset(&mut self, preset: RulePreset) {
+        let rule = Rule::parse(preset.rulestring()).expect("preset rulestrings are valid");
+        self.apply_rule(rule);
+    }
+}
+
+/// A one-line description of a preset rule, followed by its rulestring, such
+/// as `"Like Life, with a small self-replicating pattern (B36/S23)"`.
+#[wasm_bindgen]
+pub fn preset_description(preset: RulePreset) -> String {
+    format!("{} ({})", preset.description(), preset.rulestring())
+}
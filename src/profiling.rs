@@ -0,0 +1,94 @@
+// Measuring how long ticks take.
+
+use wasm_bindgen::prelude::*;
+
+use crate::platform::now;
+use crate::Universe;
+
+// How many recent ticks the rolling average covers.
+const WINDOW: usize = 60;
+
+/// Timing of calls to `tick` and `tick_n`, as returned by
+/// `Universe::stats`. Durations are in milliseconds, and a `tick_n` call
+/// counts as a single tick however many generations it runs.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TickStats {
+    pub last_ms: f64,
+    /// The average over the last 60 ticks.
+    pub average_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// Generations run since the statistics were last reset.
+    pub generations: u32,
+}
+
+// Tick durations, keeping the most recent in a ring for the rolling
+// average.
+#[derive(Clone, Debug)]
+pub struct TickTimes {
+    recent: [f64; WINDOW],
+    ticks: usize,
+    stats: TickStats,
+}
+
+impl Default for TickTimes {
+    fn default() -> Self {
+        TickTimes {
+            recent: [0.0; WINDOW],
+            ticks: 0,
+            stats: TickStats::default(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Log how long each `tick` and `tick_n` takes with `console.time`. Off
+    /// by default, since logging every tick floods the console.
+    ///
+    /// Ticks are timed either way; see `stats`.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiling = enabled;
+    }
+
+    /// How many milliseconds the last `tick` or `tick_n` call took, or
+    /// `undefined` before the first.
+    pub fn last_tick_ms(&self) -> Option<f64> {
+        (self.tick_times.ticks > 0).then_some(self.tick_times.stats.last_ms)
+    }
+
+    /// Timing of recent ticks, for showing a frame time or FPS readout.
+    /// Every field is zero until the first tick.
+    pub fn stats(&self) -> TickStats {
+        self.tick_times.stats
+    }
+
+    /// Forget the timings and generation count collected so far.
+    pub fn reset_stats(&mut self) {
+        self.tick_times = TickTimes::default();
+    }
+}
+
+impl Universe {
+    // Note the time taken by a tick that started at `started` and ran
+    // `generations` generations.
+    pub(crate) fn finish_timing(&mut self, started: f64, generations: u32) {
+        let elapsed = now() - started;
+        let times = &mut self.tick_times;
+        times.recent[times.ticks % WINDOW] = elapsed;
+        times.ticks += 1;
+
+        let stats = &mut times.stats;
+        let counted = times.ticks.min(WINDOW);
+        stats.average_ms = times.recent[..counted].iter().sum::<f64>() / counted as f64;
+        if times.ticks == 1 {
+            (stats.min_ms, stats.max_ms) = (elapsed, elapsed);
+        } else {
+            stats.min_ms = stats.min_ms.min(elapsed);
+            stats.max_ms = stats.max_ms.max(elapsed);
+        }
+        stats.last_ms = elapsed;
+        stats.generations = stats.generations.wrapping_add(generations);
+    }
+}
@@ -0,0 +1,165 @@
+// Filling the board, or part of it, with random cells.
+//
+// Symmetric soups are filled one orbit of cells at a time: each cell and
+// its images under the symmetry share one random draw, made when the first
+// of them in row-major order is reached.
+
+use wasm_bindgen::prelude::*;
+
+use crate::population::Changes;
+use crate::rng::Rng;
+use crate::{Universe, ALIVE, DEAD};
+
+// The symmetries `randomize_symmetric` can give a soup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Symmetry {
+    // Unchanged by a half turn.
+    C2,
+    // Unchanged by a quarter turn.
+    C4,
+    // Mirrored left to right.
+    D2,
+    // Mirrored left to right and top to bottom.
+    D4,
+}
+
+impl Symmetry {
+    fn parse(name: &str) -> Result<Symmetry, JsValue> {
+        match name.trim().to_uppercase().as_str() {
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D4" => Ok(Symmetry::D4),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown symmetry '{}'; expected 'C2', 'C4', 'D2' or 'D4'",
+                name.trim()
+            ))),
+        }
+    }
+
+    // The positions the cell at (`row`, `col`) is carried to on a `width`
+    // by `height` board, itself included.
+    fn images(self, row: u32, col: u32, width: u32, height: u32) -> Vec<(u32, u32)> {
+        let (flip_row, flip_col) = (height - 1 - row, width - 1 - col);
+        match self {
+            Symmetry::C2 => vec![(row, col), (flip_row, flip_col)],
+            // Only on square boards, where `width` and `height` agree.
+            Symmetry::C4 => vec![
+                (row, col),
+                (col, flip_row),
+                (flip_row, flip_col),
+                (flip_col, row),
+            ],
+            Symmetry::D2 => vec![(row, col), (row, flip_col)],
+            Symmetry::D4 => vec![
+                (row, col),
+                (row, flip_col),
+                (flip_row, col),
+                (flip_row, flip_col),
+            ],
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Fill the universe at random like `randomize`, with each cell alive
+    /// with probability `density`: 0 leaves every cell dead and 1 brings
+    /// every cell to life. Throws an error unless `density` is between 0
+    /// and 1.
+    pub fn randomize_with_density(&mut self, density: f64) -> Result<(), JsValue> {
+        check_density(density)?;
+        self.remember();
+        for idx in 0..self.cells.len() {
+            let state = random_state(&mut self.rng, density);
+            self.set_state(idx, state);
+        }
+        self.changes = Changes::default();
+        Ok(())
+    }
+
+    /// Fill just the `height` by `width` rectangle with its top-left corner
+    /// at (`row`, `col`) at random, as `randomize_with_density` does,
+    /// leaving the rest of the board alone. The rectangle wraps at the
+    /// edges on a torus and is cut off otherwise.
+    pub fn randomize_region(
+        &mut self,
+        row: u32,
+        col: u32,
+        height: u32,
+        width: u32,
+        density: f64,
+    ) -> Result<(), JsValue> {
+        check_density(density)?;
+        self.remember();
+        for r in 0..height {
+            for c in 0..width {
+                if let Some(idx) = self.offset_index(row.into(), col.into(), r, c) {
+                    let state = random_state(&mut self.rng, density);
+                    self.set_state(idx, state);
+                }
+            }
+        }
+        self.changes = Changes::default();
+        Ok(())
+    }
+
+    /// Fill the universe at random like `randomize_with_density`, but with
+    /// the soup unchanged by `symmetry`: `"C2"` a half turn, `"C4"` a
+    /// quarter turn, `"D2"` mirroring left to right or `"D4"` mirroring
+    /// both left to right and top to bottom. Where a side has an odd
+    /// length, the middle row or column is its own mirror image.
+    ///
+    /// Throws an error if the symmetry is unknown, `density` isn't between
+    /// 0 and 1, or `"C4"` is asked for on a board that isn't square.
+    pub fn randomize_symmetric(&mut self, density: f64, symmetry: &str) -> Result<(), JsValue> {
+        check_density(density)?;
+        let symmetry = Symmetry::parse(symmetry)?;
+        if symmetry == Symmetry::C4 && self.width != self.height {
+            return Err(JsValue::from_str(&format!(
+                "C4 symmetry needs a square universe, not {}x{}",
+                self.width, self.height
+            )));
+        }
+        self.remember();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let first = symmetry
+                    .images(row, col, self.width, self.height)
+                    .into_iter()
+                    .map(|(r, c)| self.get_index(r, c))
+                    .min()
+                    .unwrap_or(idx);
+                let state = if first == idx {
+                    random_state(&mut self.rng, density)
+                } else {
+                    self.state(first)
+                };
+                self.set_state(idx, state);
+            }
+        }
+        self.changes = Changes::default();
+        Ok(())
+    }
+}
+
+pub(crate) fn check_density(density: f64) -> Result<(), JsValue> {
+    if !(0.0..=1.0).contains(&density) {
+        return Err(JsValue::from_str(&format!(
+            "Density must be between 0 and 1, not {}",
+            density
+        )));
+    }
+    Ok(())
+}
+
+// A live cell with probability `density`, which is exact at 0 and 1 since
+// the random numbers are below 1.
+fn random_state(rng: &mut Rng, density: f64) -> u8 {
+    if rng.next_f64() < density {
+        ALIVE
+    } else {
+        DEAD
+    }
+}
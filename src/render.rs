@@ -0,0 +1,202 @@
+// Rendering the board as text, one character per cell and one line per row.
+//
+// With the border on, the cells are framed with box-drawing lines, every
+// tenth column is numbered above the frame and every tenth row to its left:
+//
+//       0         10
+//      ┌────────────┐
+//     0│◻◼◻◻◻◻◻◻◻◻◻◻│
+//      │◻◻◼◻◻◻◻◻◻◻◻◻│
+//      └────────────┘
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, ALIVE, DEAD};
+
+// Dying cells under Generations rules.
+const DYING_GLYPH: char = '▣';
+
+// How cells are drawn as text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextStyle {
+    pub alive: char,
+    pub dead: char,
+    pub border: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            alive: '◼',
+            dead: '◻',
+            border: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set the characters `render` and the other text renderers draw live
+    /// and dead cells with, such as `#` and `.` for fonts where the default
+    /// `◼` and `◻` are the wrong width.
+    pub fn set_render_glyphs(&mut self, alive: char, dead: char) {
+        self.text_style.alive = alive;
+        self.text_style.dead = dead;
+    }
+
+    /// Frame the text renderers' output with a border, numbering every
+    /// tenth row and column, to help find coordinates while debugging. Off
+    /// by default.
+    pub fn set_render_border(&mut self, enabled: bool) {
+        self.text_style.border = enabled;
+    }
+
+    /// Render the `height` by `width` rectangle with its top-left corner at
+    /// (`row`, `col`), one line per row. Any part of the rectangle beyond
+    /// the edges of the universe is left out.
+    pub fn render_viewport(&self, row: u32, col: u32, height: u32, width: u32) -> String {
+        let rows = row.min(self.height)..row.saturating_add(height).min(self.height);
+        let cols = col.min(self.width)..col.saturating_add(width).min(self.width);
+        let mut text = String::new();
+        // Writing to a string can't fail.
+        let _ = self.write_rows(&mut text, rows, cols);
+        text
+    }
+
+    /// Render the `height` by `width` rectangle like `render_viewport`, but
+    /// wrapping around the edges, so the result is always the full size.
+    pub fn render_viewport_wrapped(&self, row: u32, col: u32, height: u32, width: u32) -> String {
+        let wrap = |start: u32, offset: u32, size: u32| {
+            ((u64::from(start) + u64::from(offset)) % u64::from(size)) as u32
+        };
+        let mut text = String::new();
+        let _ = self.write_rows(
+            &mut text,
+            (0..height).map(|r| wrap(row, r, self.height)),
+            (0..width).map(|c| wrap(col, c, self.width)),
+        );
+        text
+    }
+
+    /// Render the board in Braille, each character showing a block of cells
+    /// two wide and four tall as raised dots, so a 64x64 board takes 16
+    /// lines of 32 characters. Boards that don't divide evenly are padded
+    /// with dead cells; only live cells are raised.
+    pub fn render_braille(&self) -> String {
+        // The dot for each cell of a block, by row and then column.
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+        let mut text = String::new();
+        for top in (0..self.height).step_by(4) {
+            for left in (0..self.width).step_by(2) {
+                let mut dots = 0;
+                for (dy, row) in DOTS.iter().enumerate() {
+                    for (dx, dot) in row.iter().enumerate() {
+                        let (r, c) = (top + dy as u32, left + dx as u32);
+                        if r < self.height
+                            && c < self.width
+                            && self.state(self.get_index(r, c)) == ALIVE
+                        {
+                            dots |= dot;
+                        }
+                    }
+                }
+                text.push(char::from_u32(0x2800 + dots).unwrap_or(' '));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl Universe {
+    // Write a line for each of the given rows, drawing the cells in the
+    // given columns.
+    pub(crate) fn write_rows(
+        &self,
+        out: &mut impl fmt::Write,
+        rows: impl Iterator<Item = u32> + Clone,
+        cols: impl Iterator<Item = u32> + Clone,
+    ) -> fmt::Result {
+        if !self.text_style.border {
+            for row in rows {
+                self.write_cells(out, row, cols.clone())?;
+                out.write_char('\n')?;
+            }
+            return Ok(());
+        }
+
+        let gutter = rows.clone().max().map_or(1, |row| row.to_string().len());
+        let width = cols.clone().count();
+        let labels = format!("{:gutter$} {}", "", column_labels(cols.clone(), width));
+        writeln!(out, "{}", labels.trim_end())?;
+        writeln!(out, "{:gutter$}┌{}┐", "", "─".repeat(width))?;
+        for row in rows {
+            if row % 10 == 0 {
+                write!(out, "{:>gutter$}│", row)?;
+            } else {
+                write!(out, "{:gutter$}│", "")?;
+            }
+            self.write_cells(out, row, cols.clone())?;
+            out.write_str("│\n")?;
+        }
+        writeln!(out, "{:gutter$}└{}┘", "", "─".repeat(width))
+    }
+
+    fn write_cells(
+        &self,
+        out: &mut impl fmt::Write,
+        row: u32,
+        cols: impl Iterator<Item = u32>,
+    ) -> fmt::Result {
+        for col in cols {
+            out.write_char(self.glyph(self.state(self.get_index(row, col))))?;
+        }
+        Ok(())
+    }
+
+    fn glyph(&self, state: u8) -> char {
+        match state {
+            DEAD => self.text_style.dead,
+            ALIVE => self.text_style.alive,
+            _ => DYING_GLYPH,
+        }
+    }
+}
+
+// A line numbering every tenth column above its first cell, leaving out
+// numbers that would run into the next or past the last column.
+fn column_labels(cols: impl Iterator<Item = u32>, width: usize) -> String {
+    let mut line = String::new();
+    for (pos, col) in cols.enumerate() {
+        if col % 10 != 0 || pos < line.len() {
+            continue;
+        }
+        let label = col.to_string();
+        if pos + label.len() <= width {
+            line.extend(std::iter::repeat_n(' ', pos - line.len()));
+            line.push_str(&label);
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::empty;
+
+    #[test]
+    fn viewports_take_the_height_before_the_width() {
+        let mut universe = empty(8, 6);
+        universe.set_render_glyphs('#', '.');
+        universe.set_pattern("blinker", 1, 2).unwrap();
+        assert_eq!(universe.render_viewport(1, 1, 2, 5), ".###.\n.....\n");
+        assert_eq!(universe.render_viewport(4, 6, 3, 4), "..\n..\n");
+        assert_eq!(
+            universe.render_viewport_wrapped(5, 6, 3, 5),
+            ".....\n.....\n....#\n"
+        );
+    }
+}
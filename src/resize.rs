@@ -0,0 +1,186 @@
+// Changing the size of the universe while keeping its cells.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{check_dimensions, Universe, DEAD};
+
+// Settings for growing the universe as patterns approach its edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AutoGrow {
+    pub margin: u32,
+    pub max_dimension: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Grow the universe after each tick whenever a live cell comes within
+    /// `margin` cells of an edge, adding `margin` dead rows or columns on
+    /// that side. Neither dimension grows past `max_dimension`.
+    ///
+    /// Combine with `set_boundary("dead")` for a plane that never wraps.
+    /// Poll `resized_since_last_check` to know when to re-read `width` and
+    /// `height`.
+    pub fn set_auto_grow(&mut self, enabled: bool, margin: u32, max_dimension: u32) {
+        self.auto_grow = enabled.then_some(AutoGrow {
+            margin,
+            max_dimension,
+        });
+    }
+
+    /// Change the size of the universe, keeping its cells. Rows and columns
+    /// are cropped or padded with dead cells; `anchor` is `"top_left"` to
+    /// keep the top-left corner in place or `"center"` to keep the contents
+    /// centered.
+    ///
+    /// Resizing to the current dimensions does nothing. Throws an error if
+    /// either dimension is zero or the universe would be too large.
+    pub fn resize(&mut self, width: u32, height: u32, anchor: &str) -> Result<(), JsValue> {
+        let (row_offset, col_offset) = match anchor.trim().to_lowercase().as_str() {
+            "top_left" => (0, 0),
+            "center" => (
+                (i64::from(height) - i64::from(self.height)) / 2,
+                (i64::from(width) - i64::from(self.width)) / 2,
+            ),
+            _ => {
+                return Err(JsValue::from_str(&format!(
+                    "Unknown anchor '{}'; expected 'top_left' or 'center'",
+                    anchor.trim()
+                )))
+            }
+        };
+        check_dimensions(width, height).map_err(|e| JsValue::from_str(&e))?;
+        if (width, height) != (self.width, self.height) {
+            self.remember();
+            self.reframe(width, height, row_offset, col_offset);
+        }
+        Ok(())
+    }
+
+    /// Shrink or grow the universe to the smallest rectangle holding every
+    /// cell that isn't dead, plus `padding` dead cells on each side. An
+    /// empty universe is left as it is.
+    pub fn crop_to_content(&mut self, padding: u32) -> Result<(), JsValue> {
+        let Some((min_row, max_row, min_col, max_col)) = self.occupied_bounds() else {
+            return Ok(());
+        };
+        let padded = |min: u32, max: u32| {
+            padding
+                .checked_mul(2)
+                .and_then(|extra| (max - min + 1).checked_add(extra))
+        };
+        let (Some(width), Some(height)) = (padded(min_col, max_col), padded(min_row, max_row))
+        else {
+            return Err(JsValue::from_str(&format!(
+                "Padding of {} makes the universe too large",
+                padding
+            )));
+        };
+        check_dimensions(width, height).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
+        self.reframe(
+            width,
+            height,
+            i64::from(padding) - i64::from(min_row),
+            i64::from(padding) - i64::from(min_col),
+        );
+        Ok(())
+    }
+
+    /// The smallest rectangle holding every cell that isn't dead, as
+    /// `[min_row, min_col, max_row, max_col]` with both corners included, or
+    /// an empty array when every cell is dead.
+    pub fn content_bounds(&self) -> js_sys::Uint32Array {
+        let bounds: Vec<u32> = self
+            .occupied_bounds()
+            .map(|(min_row, max_row, min_col, max_col)| vec![min_row, min_col, max_row, max_col])
+            .unwrap_or_default();
+        js_sys::Uint32Array::from(&bounds[..])
+    }
+
+    /// Whether the universe has changed size since the last call.
+    pub fn resized_since_last_check(&mut self) -> bool {
+        std::mem::take(&mut self.resized)
+    }
+}
+
+impl Universe {
+    pub(crate) fn grow_to_fit(&mut self, growth: AutoGrow) {
+        let Some((min_row, max_row, min_col, max_col)) = self.occupied_bounds() else {
+            return;
+        };
+        let margin = growth.margin;
+
+        // How many rows or columns to add before and after, within the
+        // space `max_dimension` leaves.
+        let extend = |min: u32, max: u32, size: u32| {
+            let mut room = growth.max_dimension.saturating_sub(size);
+            let mut take = |wanted: bool| {
+                let amount = if wanted { margin.min(room) } else { 0 };
+                room -= amount;
+                amount
+            };
+            let before = take(min < margin);
+            let after = take(size - 1 - max < margin);
+            (before, after)
+        };
+        let (top, bottom) = extend(min_row, max_row, self.height);
+        let (left, right) = extend(min_col, max_col, self.width);
+
+        let width = self.width + left + right;
+        let height = self.height + top + bottom;
+        if (width, height) != (self.width, self.height) && check_dimensions(width, height).is_ok() {
+            self.reframe(width, height, top.into(), left.into());
+        }
+    }
+
+    // Bounding box of the cells that aren't dead, as (min_row, max_row,
+    // min_col, max_col).
+    pub(crate) fn occupied_bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        let width = self.width as usize;
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for idx in (0..self.cells.len()).filter(|&idx| self.cells[idx] != DEAD) {
+            let (row, col) = ((idx / width) as u32, (idx % width) as u32);
+            bounds = Some(match bounds {
+                None => (row, row, col, col),
+                Some((r0, r1, c0, c1)) => (r0.min(row), r1.max(row), c0.min(col), c1.max(col)),
+            });
+        }
+        bounds
+    }
+
+    // Give the universe new dimensions, moving every cell (and ant) down by
+    // `row_offset` and right by `col_offset`. Anything that ends up outside
+    // the new grid is dropped and new space is dead.
+    pub(crate) fn reframe(&mut self, width: u32, height: u32, row_offset: i64, col_offset: i64) {
+        let mut cells = vec![DEAD; width as usize * height as usize];
+        let moved = |row: u32, col: u32| {
+            let r = u32::try_from(i64::from(row) + row_offset)
+                .ok()
+                .filter(|&r| r < height)?;
+            let c = u32::try_from(i64::from(col) + col_offset)
+                .ok()
+                .filter(|&c| c < width)?;
+            Some((r, c))
+        };
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if let Some((r, c)) = moved(row, col) {
+                    cells[(r * width + c) as usize] = self.cells[self.get_index(row, col)];
+                }
+            }
+        }
+        self.ants.retain_mut(|ant| match moved(ant.row, ant.col) {
+            Some((row, col)) => {
+                (ant.row, ant.col) = (row, col);
+                true
+            }
+            None => false,
+        });
+
+        self.width = width;
+        self.height = height;
+        self.replace_cells(cells);
+        self.resized = true;
+    }
+}
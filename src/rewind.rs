@@ -0,0 +1,169 @@
+// Stepping the simulation back through the generations it just ran.
+//
+// Life can't be run in reverse, so the board is saved before every
+// generation, packed as for undo, and stepping back puts the latest copy
+// back. At the default 64 generations a 256x256 board costs 512KB. Once
+// the ring is full, each generation is saved over the oldest, so ticking
+// doesn't allocate.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::undo::SavedBoard;
+use crate::Universe;
+
+const DEFAULT_CAPACITY: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct Rewind {
+    capacity: usize,
+    // The board before each recent generation, most recent last.
+    boards: VecDeque<SavedBoard>,
+}
+
+impl Default for Rewind {
+    fn default() -> Self {
+        Rewind {
+            capacity: DEFAULT_CAPACITY,
+            boards: VecDeque::new(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Keep the last `capacity` generations for `step_back`, 64 by default,
+    /// dropping the oldest first. Each costs a bit per cell, or a byte per
+    /// cell under automata with more than two states; 0 turns stepping back
+    /// off.
+    pub fn set_step_back_capacity(&mut self, capacity: u32) {
+        self.rewind.capacity = capacity as usize;
+        while self.rewind.boards.len() > self.rewind.capacity {
+            self.rewind.boards.pop_front();
+        }
+    }
+
+    /// Whether `step_back` can return to the previous generation.
+    pub fn can_step_back(&self) -> bool {
+        self.rewind
+            .boards
+            .back()
+            .is_some_and(|board| board.generation + 1 == self.generation)
+    }
+
+    /// Go back one generation, undoing the last tick along with any edits
+    /// made since. Returns false when the previous generation isn't kept,
+    /// such as right after clearing or loading a pattern.
+    ///
+    /// Ticking again runs forward from there, replacing the generations
+    /// stepped back over.
+    pub fn step_back(&mut self) -> bool {
+        if !self.can_step_back() {
+            return false;
+        }
+        self.remember();
+        if let Some(board) = self.rewind.boards.pop_back() {
+            self.load_board(&board);
+        }
+        true
+    }
+}
+
+impl Universe {
+    // Save the board before running a generation.
+    pub(crate) fn save_generation(&mut self) {
+        if self.rewind.capacity == 0 {
+            return;
+        }
+        if self.rewind.boards.len() == self.rewind.capacity {
+            if let Some(mut oldest) = self.rewind.boards.pop_front() {
+                self.save_board_into(&mut oldest);
+                self.rewind.boards.push_back(oldest);
+                return;
+            }
+        }
+        let board = self.save_board();
+        self.rewind.boards.push_back(board);
+    }
+
+    // Stop saving generations, setting aside the boards kept so far for
+    // `resume_rewind`.
+    pub(crate) fn pause_rewind(&mut self) -> Rewind {
+        std::mem::replace(
+            &mut self.rewind,
+            Rewind {
+                capacity: 0,
+                boards: VecDeque::new(),
+            },
+        )
+    }
+
+    // Put back the boards set aside by `pause_rewind`, dropping any from the
+    // current generation on, which were saved on a run since gone back over.
+    pub(crate) fn resume_rewind(&mut self, mut rewind: Rewind) {
+        rewind
+            .boards
+            .retain(|board| board.generation < self.generation);
+        self.rewind = rewind;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::soup;
+
+    #[test]
+    fn full_ring_reuses_its_boards() {
+        let mut universe = soup(40, 30, 0.3, 14);
+        universe.set_step_back_capacity(4);
+        universe.tick_n(4);
+        let buffers = |universe: &crate::Universe| {
+            let mut buffers: Vec<_> = universe
+                .rewind
+                .boards
+                .iter()
+                .map(|board| board.data.as_ptr())
+                .collect();
+            buffers.sort();
+            buffers
+        };
+        let kept = buffers(&universe);
+
+        let mut generations = vec![universe.cells.clone()];
+        for _ in 0..10 {
+            universe.tick();
+            generations.push(universe.cells.clone());
+        }
+        assert_eq!(buffers(&universe), kept);
+
+        generations.pop();
+        for _ in 0..4 {
+            assert!(universe.step_back());
+            assert_eq!(universe.cells, generations.pop().unwrap());
+        }
+        assert!(!universe.step_back());
+    }
+
+    #[test]
+    fn rolling_back_keeps_one_board_per_generation() {
+        let mut universe = soup(40, 30, 0.3, 15);
+        universe.enable_checkpoints(10, 5);
+        let mut generations = vec![universe.cells.clone()];
+        for _ in 0..30 {
+            universe.tick();
+            generations.push(universe.cells.clone());
+        }
+
+        universe.rollback_to(25).unwrap();
+        let saved: Vec<u64> = universe
+            .rewind
+            .boards
+            .iter()
+            .map(|board| board.generation)
+            .collect();
+        assert_eq!(saved, (0..25).collect::<Vec<_>>());
+        assert!(universe.step_back());
+        assert_eq!(universe.cells, generations[24]);
+    }
+}
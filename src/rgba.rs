@@ -0,0 +1,191 @@
+// Rendering the board as RGBA pixels, for `putImageData` or image files.
+//
+// Each cell becomes a `cell_size` by `cell_size` square, so the image is
+// `width * cell_size` by `height * cell_size` pixels, four bytes each.
+// Colors are packed as 0xRRGGBBAA; dying cells are drawn in the dead color.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, ALIVE};
+
+// The most pixels an image may have, 256MB of RGBA.
+const MAX_PIXELS: usize = 1 << 26;
+
+#[wasm_bindgen]
+impl Universe {
+    /// The board as a new RGBA buffer, four bytes per pixel in row-major
+    /// order. Throws an error if `cell_size` is 0 or the image would have
+    /// more than 2^26 pixels.
+    pub fn render_rgba(
+        &self,
+        cell_size: u32,
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<Vec<u8>, JsValue> {
+        let mut pixels = vec![0; self.rgba_len(cell_size)?];
+        self.render_rgba_into(&mut pixels, cell_size, alive_color, dead_color)?;
+        Ok(pixels)
+    }
+
+    /// Render the board into a buffer the caller keeps between frames,
+    /// which must be exactly `width * cell_size * height * cell_size * 4`
+    /// bytes long.
+    pub fn render_rgba_into(
+        &self,
+        pixels: &mut [u8],
+        cell_size: u32,
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<(), JsValue> {
+        let len = self.rgba_len(cell_size)?;
+        if pixels.len() != len {
+            return Err(JsValue::from_str(&format!(
+                "Buffer holds {} bytes, but the image needs {}",
+                pixels.len(),
+                len
+            )));
+        }
+        render(
+            &self.cells,
+            self.width as usize,
+            cell_size as usize,
+            alive_color.to_be_bytes(),
+            dead_color.to_be_bytes(),
+            pixels,
+        );
+        Ok(())
+    }
+
+    /// The board as `ImageData`, ready for `putImageData`. The pixels are
+    /// rendered into a buffer the universe keeps, so no memory is allocated
+    /// from one frame to the next.
+    ///
+    /// The image data is a view of the universe's memory: draw it straight
+    /// away, since the next call overwrites it and other calls may move it.
+    #[cfg(feature = "web")]
+    pub fn render_rgba_into_image_data(
+        &mut self,
+        cell_size: u32,
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<web_sys::ImageData, JsValue> {
+        let mut pixels = std::mem::take(&mut self.rgba);
+        pixels.resize(self.rgba_len(cell_size)?, 0);
+        self.render_rgba_into(&mut pixels, cell_size, alive_color, dead_color)?;
+        self.rgba = pixels;
+        web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&self.rgba),
+            self.width * cell_size,
+            self.height * cell_size,
+        )
+    }
+
+    /// A `target_width` by `target_height` overview of the board as RGBA
+    /// pixels, for a minimap. Each pixel covers a block of cells and is
+    /// shaded from black to white by the fraction of them alive, so small
+    /// patterns stay visible however far the board is shrunk.
+    ///
+    /// Blocks differ in size by at most a cell when the sizes don't divide
+    /// evenly. Throws an error if either size is 0 or the image would have
+    /// more than 2^26 pixels.
+    pub fn render_minimap(
+        &self,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<Vec<u8>, JsValue> {
+        let pixels = (target_width as usize)
+            .checked_mul(target_height as usize)
+            .filter(|&pixels| pixels > 0 && pixels <= MAX_PIXELS)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "Can't make a {}x{} minimap",
+                    target_width, target_height
+                ))
+            })?;
+
+        let mut image = Vec::with_capacity(pixels * 4);
+        for y in 0..target_height {
+            let rows = block(y, target_height, self.height);
+            for x in 0..target_width {
+                let cols = block(x, target_width, self.width);
+                let live = rows
+                    .clone()
+                    .flat_map(|row| cols.clone().map(move |col| (row, col)))
+                    .filter(|&(row, col)| self.state(self.get_index(row, col)) == ALIVE)
+                    .count();
+                let cells = rows.len() * cols.len();
+                let shade = ((live * 255 + cells / 2) / cells) as u8;
+                image.extend_from_slice(&[shade, shade, shade, 255]);
+            }
+        }
+        Ok(image)
+    }
+}
+
+// The cells along one side covered by the `index`th of `blocks` blocks
+// spanning `size` cells. When there are more blocks than cells, each covers
+// the one cell it falls in.
+fn block(index: u32, blocks: u32, size: u32) -> std::ops::Range<u32> {
+    let edge = |i: u32| (u64::from(i) * u64::from(size) / u64::from(blocks)) as u32;
+    let start = edge(index);
+    start..edge(index + 1).max(start + 1)
+}
+
+impl Universe {
+    // The length in bytes of the board's image with cells of the given
+    // size.
+    fn rgba_len(&self, cell_size: u32) -> Result<usize, JsValue> {
+        if cell_size == 0 {
+            return Err(JsValue::from_str("Cell size must be at least 1"));
+        }
+        let side = cell_size as usize;
+        side.checked_mul(side)
+            .and_then(|area| area.checked_mul(self.cells.len()))
+            .filter(|&pixels| pixels <= MAX_PIXELS)
+            .map(|pixels| pixels * 4)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "A {}x{} image with {} pixel cells is too large",
+                    self.width, self.height, cell_size
+                ))
+            })
+    }
+}
+
+// Fill `pixels`, which must be exactly the right size, with the image of
+// `cells` in rows of `width`.
+pub fn render(
+    cells: &[u8],
+    width: usize,
+    cell_size: usize,
+    alive: [u8; 4],
+    dead: [u8; 4],
+    pixels: &mut [u8],
+) {
+    let color = |state: u8| if state == ALIVE { alive } else { dead };
+
+    if cell_size == 1 {
+        for (pixel, &state) in pixels.chunks_exact_mut(4).zip(cells) {
+            pixel.copy_from_slice(&color(state));
+        }
+        return;
+    }
+
+    // Draw the first pixel row of each cell row, then copy it down.
+    let row_bytes = width * cell_size * 4;
+    for (band, row) in pixels
+        .chunks_exact_mut(row_bytes * cell_size)
+        .zip(cells.chunks_exact(width))
+    {
+        let (first, rest) = band.split_at_mut(row_bytes);
+        for (cell, &state) in first.chunks_exact_mut(cell_size * 4).zip(row) {
+            let color = color(state);
+            for pixel in cell.chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+        for copy in rest.chunks_exact_mut(row_bytes) {
+            copy.copy_from_slice(first);
+        }
+    }
+}
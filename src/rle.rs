@@ -0,0 +1,328 @@
+// Run Length Encoded (RLE) pattern support, the format used by LifeWiki and
+// Golly.
+//
+// A file has optional `#` comment lines, a header such as
+// `x = 3, y = 3, rule = B3/S23`, and a body of `<count><tag>` items where the
+// tag is `b` (dead), `o` (alive) or `$` (end of row), terminated by `!`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::pattern::Pattern;
+use crate::{PlacementMode, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Load a pattern in RLE format with its top-left corner at the given
+    /// position.
+    ///
+    /// The pattern's bounding box is cleared first and placement wraps at the
+    /// edges like `set_pattern`. Malformed input is rejected without touching
+    /// the universe; otherwise the generation counter restarts at 0.
+    pub fn load_rle(&mut self, rle: &str, start_row: u32, start_col: u32) -> Result<(), JsValue> {
+        let pattern = parse(rle).map_err(|e| JsValue::from_str(&e))?;
+        self.remember();
+        self.place(&pattern, start_row, start_col, PlacementMode::Replace);
+        self.generation = 0;
+        Ok(())
+    }
+
+    /// Export the live cells as an RLE string, trimmed to their bounding box.
+    ///
+    /// Lines are wrapped at 70 characters like Golly's output. A clear
+    /// universe produces an empty `x = 0, y = 0` pattern.
+    pub fn to_rle(&self) -> String {
+        encode(&self.live_pattern(), &self.rule())
+    }
+}
+
+// Golly keeps RLE body lines at or under this many characters.
+const LINE_LENGTH: usize = 70;
+
+pub fn parse(rle: &str) -> Result<Pattern, String> {
+    let mut size = None;
+    let mut cells = Vec::new();
+    let (mut row, mut col) = (0u32, 0u32);
+    let mut run: Option<u32> = None;
+
+    'lines: for (number, line) in rle.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (width, height) = match size {
+            Some(size) => size,
+            None => {
+                size = Some(parse_header(line).map_err(|e| format!("line {}: {}", number, e))?);
+                continue;
+            }
+        };
+
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => {
+                    let digit = ch.to_digit(10).unwrap();
+                    let count = run
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or_else(|| format!("line {}: run count is too large", number))?;
+                    run = Some(count);
+                }
+                'b' => col = col.saturating_add(run.take().unwrap_or(1)),
+                'o' => {
+                    let count = run.take().unwrap_or(1);
+                    if row >= height || col.saturating_add(count) > width {
+                        return Err(format!(
+                            "line {}: live cells extend past the declared size x = {}, y = {}",
+                            number, width, height
+                        ));
+                    }
+                    cells.extend((col..col + count).map(|c| (row, c)));
+                    col += count;
+                }
+                '$' => {
+                    row = row.saturating_add(run.take().unwrap_or(1));
+                    col = 0;
+                }
+                '!' => {
+                    run = None;
+                    break 'lines;
+                }
+                c if c.is_whitespace() => {}
+                other => {
+                    return Err(format!(
+                        "line {}: unexpected character '{}' in pattern body",
+                        number, other
+                    ));
+                }
+            }
+        }
+    }
+
+    if run.is_some() {
+        return Err("pattern body ends with a run count that has no tag".to_string());
+    }
+
+    let (width, height) = size.ok_or("missing `x = <width>, y = <height>` header line")?;
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+pub fn encode(pattern: &Pattern, rule: &str) -> String {
+    let mut cells = pattern.cells.clone();
+    cells.sort_unstable();
+
+    // Walk the live cells in reading order, emitting the gap before each one.
+    // Trailing dead cells in a row are never written.
+    let mut items: Vec<(u32, char)> = Vec::new();
+    let (mut row, mut col) = (0, 0);
+    for (r, c) in cells {
+        if r > row {
+            push_run(&mut items, r - row, '$');
+            row = r;
+            col = 0;
+        }
+        push_run(&mut items, c - col, 'b');
+        push_run(&mut items, 1, 'o');
+        col = c + 1;
+    }
+
+    let mut tokens: Vec<String> = items
+        .into_iter()
+        .map(|(count, tag)| match count {
+            1 => tag.to_string(),
+            n => format!("{}{}", n, tag),
+        })
+        .collect();
+    tokens.push("!".to_string());
+
+    let mut out = format!(
+        "x = {}, y = {}, rule = {}\n",
+        pattern.width, pattern.height, rule
+    );
+    let mut line_len = 0;
+    for token in tokens {
+        if line_len > 0 && line_len + token.len() > LINE_LENGTH {
+            out.push('\n');
+            line_len = 0;
+        }
+        line_len += token.len();
+        out.push_str(&token);
+    }
+    out.push('\n');
+    out
+}
+
+// The rule named in the header line, if there is one.
+pub fn header_rule(rle: &str) -> Option<&str> {
+    header_line(rle)?
+        .split(',')
+        .filter_map(|field| field.split_once('='))
+        .find(|(key, _)| key.trim() == "rule")
+        .map(|(_, value)| value.trim())
+}
+
+// The width and height declared in the header line, read without going
+// through the pattern body.
+pub fn header_size(rle: &str) -> Result<(u32, u32), String> {
+    let header = header_line(rle).ok_or("missing `x = <width>, y = <height>` header line")?;
+    parse_header(header)
+}
+
+// The first line that isn't blank or a comment.
+fn header_line(rle: &str) -> Option<&str> {
+    rle.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+// Append a run to the item list, merging it into the previous run when the
+// tags match.
+fn push_run(items: &mut Vec<(u32, char)>, count: u32, tag: char) {
+    if count == 0 {
+        return;
+    }
+    match items.last_mut() {
+        Some((n, t)) if *t == tag => *n += count,
+        _ => items.push((count, tag)),
+    }
+}
+
+// Parse the `x = <width>, y = <height>[, rule = <rule>]` header line. The rule
+// is accepted but not applied; `set_rule` changes the universe's rule.
+fn parse_header(line: &str) -> Result<(u32, u32), String> {
+    let mut width = None;
+    let mut height = None;
+
+    for field in line.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key = value` in header, found `{}`", field.trim()))?;
+        let (key, value) = (key.trim(), value.trim());
+        match key {
+            "x" | "y" => {
+                let n = value.parse::<u32>().map_err(|_| {
+                    format!("header value `{} = {}` is not a valid size", key, value)
+                })?;
+                if key == "x" {
+                    width = Some(n);
+                } else {
+                    height = Some(n);
+                }
+            }
+            "rule" => {}
+            _ => return Err(format!("unknown header field `{}`", key)),
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(format!("header `{}` must declare both x and y", line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, parse};
+
+    // The live cells of a parsed pattern in reading order.
+    fn cells(rle: &str) -> Vec<(u32, u32)> {
+        let mut cells = parse(rle).unwrap().cells;
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn reads_a_glider() {
+        let pattern = parse("x = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.cells, [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn reads_a_lightweight_spaceship() {
+        assert_eq!(
+            cells("x = 5, y = 4, rule = B3/S23\nbo2bo$o4b$o3bo$4o!"),
+            [
+                (0, 1),
+                (0, 4),
+                (1, 0),
+                (2, 0),
+                (2, 4),
+                (3, 0),
+                (3, 1),
+                (3, 2),
+                (3, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_runs_of_several_digits() {
+        let rle = "x = 25, y = 13\n12b13o12$10bo!";
+        let expected: Vec<_> = (12..25).map(|col| (0, col)).chain([(12, 10)]).collect();
+        assert_eq!(cells(rle), expected);
+    }
+
+    #[test]
+    fn skips_comments_and_whitespace() {
+        let rle = "#N Glider\n#C A comment\n\n  x = 3, y = 3  \nb o\n b$2b\n\no$\n#C between\n3o !";
+        assert_eq!(cells(rle), [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn stops_at_the_bang() {
+        assert_eq!(cells("x = 2, y = 1\no!bo\nthis is ignored"), [(0, 0)]);
+        assert_eq!(
+            parse("x = 2, y = 1\n2").unwrap_err(),
+            "pattern body ends with a run count that has no tag"
+        );
+    }
+
+    #[test]
+    fn explains_a_broken_header() {
+        for (rle, error) in [
+            (
+                "x = 3 y = 3\n3o!",
+                "line 1: header value `x = 3 y = 3` is not a valid size",
+            ),
+            (
+                "#C glider\nx = three, y = 3\n3o!",
+                "line 2: header value `x = three` is not a valid size",
+            ),
+            (
+                "x = 3\n3o!",
+                "line 1: header `x = 3` must declare both x and y",
+            ),
+            (
+                "x = 3, y\n3o!",
+                "line 1: expected `key = value` in header, found `y`",
+            ),
+            (
+                "x = 3, y = 1, z = 2\n3o!",
+                "line 1: unknown header field `z`",
+            ),
+            (
+                "#C nothing else",
+                "missing `x = <width>, y = <height>` header line",
+            ),
+        ] {
+            assert_eq!(parse(rle).unwrap_err(), error);
+        }
+        assert_eq!(
+            parse("x = 2, y = 1\n3o!").unwrap_err(),
+            "line 2: live cells extend past the declared size x = 2, y = 1"
+        );
+    }
+
+    #[test]
+    fn encodes_what_it_reads() {
+        let rle = "x = 25, y = 13, rule = B3/S23\n12b13o12$10bo!\n";
+        assert_eq!(encode(&parse(rle).unwrap(), "B3/S23"), rle);
+    }
+}
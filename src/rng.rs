@@ -0,0 +1,130 @@
+// A small seeded random number generator, so a board filled at random can
+// be filled the same way again from its seed.
+//
+// This is xoshiro256** by Blackman and Vigna, with its state filled from
+// the seed by SplitMix64, as its authors suggest.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[derive(Clone, Debug)]
+pub struct Rng {
+    seed: u64,
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        let mut mix = seed;
+        let mut split_mix = || {
+            mix = mix.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = mix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        let state = [split_mix(), split_mix(), split_mix(), split_mix()];
+        Rng { seed, state }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    // A number in `[0, 1)`, from the top 53 bits so every value is equally
+    // likely.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Restart the random number generator from `seed`. `randomize` and the
+    /// other methods that fill cells or change the rule at random draw from
+    /// it, so two universes given the same seed and then the same calls end
+    /// up with the same board. Each universe starts from a random seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// The seed the random number generator last started from, to show or
+    /// share so the same random board can be made again.
+    pub fn current_seed(&self) -> u64 {
+        self.rng.seed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use crate::testing::empty;
+
+    #[test]
+    fn matches_the_reference_generator() {
+        // The first outputs of the authors' xoshiro256** from the state
+        // {1, 2, 3, 4}.
+        let mut rng = Rng {
+            seed: 0,
+            state: [1, 2, 3, 4],
+        };
+        let expected = [
+            0x0000_0000_0000_2d00,
+            0x0000_0000_0000_0000,
+            0x0000_0000_5a00_7080,
+            0x10e0_0000_0000_9d80,
+            0x10e0_b61c_e100_9d80,
+            0x0870_021c_e143_ad00,
+        ];
+        for value in expected {
+            assert_eq!(rng.next_u64(), value);
+        }
+    }
+
+    #[test]
+    fn seeds_through_split_mix() {
+        // The same, with the state filled by the authors' SplitMix64 from
+        // the seed 42.
+        let mut rng = Rng::new(42);
+        let expected = [
+            0x1578_0b2e_0c2e_c716,
+            0x6104_d986_6d11_3a7e,
+            0xae17_5332_39e4_99a1,
+            0xecb8_ad47_03b3_60a1,
+        ];
+        for value in expected {
+            assert_eq!(rng.next_u64(), value);
+        }
+    }
+
+    #[test]
+    fn same_seed_makes_the_same_soup() {
+        let soup = |seed| {
+            let mut universe = empty(64, 48);
+            universe.set_seed(seed);
+            universe.randomize();
+            let first = universe.cells.clone();
+            universe.randomize_with_density(0.6).unwrap();
+            (first, universe.cells.clone(), universe.current_seed())
+        };
+        let (first, second, seed) = soup(7);
+        assert_eq!(seed, 7);
+        assert_eq!(soup(7), (first.clone(), second.clone(), 7));
+        assert_ne!(first, second);
+        assert_ne!(soup(8).0, first);
+    }
+}
@@ -0,0 +1,198 @@
+// Life-like rules written as B/S rulestrings, such as `B3/S23` for Conway's
+// Game of Life or `B36/S23` for HighLife. Generations rules add a `C` part
+// giving the number of cell states, as in `B2/S/C3` for Brian's Brain.
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::{Universe, ALIVE, DEAD};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Change the rule used by `tick` to a B/S rulestring such as `B36/S23`
+    /// (HighLife), `B2/S` (Seeds) or `B3678/S34678` (Day & Night).
+    ///
+    /// The birth and survival parts may come in either order and are case
+    /// insensitive, and an optional `C` part such as `B2/S/C3` sets the
+    /// number of states for a Generations rule. An invalid rulestring leaves
+    /// the current rule unchanged.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        let rule = Rule::parse(rule).map_err(|e| JsValue::from_str(&e))?;
+        self.apply_rule(rule);
+        Ok(())
+    }
+
+    /// Switch to a Generations rule with `states` cell states.
+    ///
+    /// `birth` and `survival` are neighbour counts such as `"2"` or `"345"`.
+    /// A live cell that fails to survive enters dying state 2 and then
+    /// advances one state per tick until it dies after state `states - 1`.
+    /// Only live cells count as neighbours. With `states = 2` this is an
+    /// ordinary Life-like rule.
+    pub fn set_rule_generations(
+        &mut self,
+        birth: &str,
+        survival: &str,
+        states: u8,
+    ) -> Result<(), JsValue> {
+        let rule = Rule::generations(birth, survival, states).map_err(|e| JsValue::from_str(&e))?;
+        self.apply_rule(rule);
+        Ok(())
+    }
+
+    /// The current rule as a canonical B/S rulestring, with a `C` part for
+    /// Generations rules.
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+}
+
+impl Universe {
+    // Install a rule and switch back to Life-like transitions, killing any
+    // cells the new rule has no state for.
+    pub(crate) fn apply_rule(&mut self, rule: Rule) {
+        self.apply_automaton(Automaton::Life, rule.states);
+        self.rule = rule;
+        self.record_rule(rule);
+    }
+}
+
+// Birth and survival conditions stored as bitmasks, where bit `n` is set when
+// a cell with `n` live neighbours is born or survives, and the number of cell
+// states: 2 for Life-like rules, more for Generations rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: u16,
+    pub survival: u16,
+    pub states: u8,
+}
+
+impl Rule {
+    // Conway's Game of Life: B3/S23.
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+    };
+
+    pub fn parse(rule: &str) -> Result<Rule, String> {
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = None;
+
+        let invalid = |e: String| format!("Invalid rule '{}': {}", rule, e);
+        for part in rule.trim().split('/') {
+            let part = part.trim();
+            let mut chars = part.chars();
+            let first = chars.next();
+            let digits = chars.as_str();
+            let repeated = match first {
+                Some('B' | 'b') => birth.replace(parse_counts(digits).map_err(invalid)?),
+                Some('S' | 's') => survival.replace(parse_counts(digits).map_err(invalid)?),
+                Some('C' | 'c') => states
+                    .replace(parse_states(digits).map_err(invalid)?)
+                    .map(u16::from),
+                _ => {
+                    return Err(format!(
+                        "Invalid rule '{}': expected B and S parts, like B3/S23",
+                        rule
+                    ))
+                }
+            };
+            if repeated.is_some() {
+                return Err(format!(
+                    "Invalid rule '{}': repeated '{}' part",
+                    rule,
+                    &part[..1]
+                ));
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule {
+                birth,
+                survival,
+                states: states.unwrap_or(2),
+            }),
+            _ => Err(format!(
+                "Invalid rule '{}': expected both B and S parts, like B3/S23",
+                rule
+            )),
+        }
+    }
+
+    // A Generations rule from bare neighbour counts, such as `("2", "", 3)`.
+    pub fn generations(birth: &str, survival: &str, states: u8) -> Result<Rule, String> {
+        let birth =
+            parse_counts(birth.trim()).map_err(|e| format!("Invalid birth counts: {}", e))?;
+        let survival =
+            parse_counts(survival.trim()).map_err(|e| format!("Invalid survival counts: {}", e))?;
+        if states < 2 {
+            return Err(format!("A rule needs at least 2 states, not {}", states));
+        }
+        Ok(Rule {
+            birth,
+            survival,
+            states,
+        })
+    }
+
+    // The state of a cell in the next generation. Live cells that fail to
+    // survive and dying cells move on to the next dying state, and die after
+    // the last one.
+    pub fn next(self, state: u8, live_neighbors: u8) -> u8 {
+        let born = self.birth & (1 << live_neighbors) != 0;
+        let survives = self.survival & (1 << live_neighbors) != 0;
+        match state {
+            DEAD if born => ALIVE,
+            DEAD => DEAD,
+            ALIVE if survives => ALIVE,
+            _ if state + 1 < self.states => state + 1,
+            _ => DEAD,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        write_counts(f, self.birth)?;
+        write!(f, "/S")?;
+        write_counts(f, self.survival)?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+// Parse the neighbour counts following a B or S, such as `23`.
+fn parse_counts(digits: &str) -> Result<u16, String> {
+    let mut mask = 0;
+    for ch in digits.chars() {
+        match ch.to_digit(10) {
+            Some(n) if n <= 8 => mask |= 1 << n,
+            _ => return Err(format!("'{}' is not a neighbour count from 0 to 8", ch)),
+        }
+    }
+    Ok(mask)
+}
+
+// Parse the state count following a C, such as `3`.
+fn parse_states(digits: &str) -> Result<u8, String> {
+    match digits.parse::<u8>() {
+        Ok(states) if states >= 2 => Ok(states),
+        _ => Err(format!("'{}' is not a state count from 2 to 255", digits)),
+    }
+}
+
+fn write_counts(f: &mut fmt::Formatter, mask: u16) -> fmt::Result {
+    for n in 0..=8 {
+        if mask & (1 << n) != 0 {
+            write!(f, "{}", n)?;
+        }
+    }
+    Ok(())
+}
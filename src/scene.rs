@@ -0,0 +1,99 @@
+// Loading several patterns at once from a JSON scene description.
+
+use wasm_bindgen::prelude::*;
+
+use crate::pattern::Pattern;
+use crate::{PlacementMode, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Place every pattern in a JSON scene description, in order.
+    ///
+    /// The scene is an array of entries such as
+    /// `{ "pattern": "glider", "row": 10, "col": 4, "rotation": 90 }`, where
+    /// `rotation` (0, 90, 180 or 270 degrees clockwise) and the `flip_h` and
+    /// `flip_v` booleans are optional. When `clear` is set the universe is
+    /// cleared first. Every entry is validated before anything is placed, so
+    /// an error naming the offending entry leaves the universe untouched.
+    pub fn load_scene(&mut self, json: &str, clear: bool) -> Result<(), JsValue> {
+        let scene =
+            js_sys::JSON::parse(json).map_err(|_| JsValue::from_str("Scene is not valid JSON"))?;
+        let entries: js_sys::Array = scene
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("Scene must be a JSON array of entries"))?;
+
+        let placements = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                self.parse_entry(&entry).map_err(|e| {
+                    let message = e.as_string().unwrap_or_default();
+                    JsValue::from_str(&format!("Scene entry {}: {}", i, message))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.remember();
+        if clear {
+            self.clear();
+        }
+        for (pattern, row, col) in placements {
+            self.place(&pattern, row, col, PlacementMode::Replace);
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    // Resolve one scene entry into an oriented pattern and its position.
+    fn parse_entry(&self, entry: &JsValue) -> Result<(Pattern, u32, u32), JsValue> {
+        if !entry.is_object() {
+            return Err(JsValue::from_str("expected an object"));
+        }
+
+        let name = field(entry, "pattern")?
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("`pattern` must be a string"))?;
+        let row = coordinate(entry, "row")?;
+        let col = coordinate(entry, "col")?;
+
+        let rotation = match field(entry, "rotation")? {
+            value if value.is_undefined() => 0,
+            value => match value.as_f64() {
+                Some(degrees) if [0.0, 90.0, 180.0, 270.0].contains(&degrees) => {
+                    (degrees / 90.0) as u8
+                }
+                _ => return Err(JsValue::from_str("`rotation` must be 0, 90, 180 or 270")),
+            },
+        };
+        let flip_h = flag(entry, "flip_h")?;
+        let flip_v = flag(entry, "flip_v")?;
+
+        let pattern = self.find_named_pattern(&name)?;
+        Ok((pattern.transformed(rotation, flip_h, flip_v), row, col))
+    }
+}
+
+fn field(entry: &JsValue, name: &str) -> Result<JsValue, JsValue> {
+    js_sys::Reflect::get(entry, &JsValue::from_str(name))
+}
+
+fn coordinate(entry: &JsValue, name: &str) -> Result<u32, JsValue> {
+    match field(entry, name)?.as_f64() {
+        Some(n) if n >= 0.0 && n <= f64::from(u32::MAX) && n.fract() == 0.0 => Ok(n as u32),
+        _ => Err(JsValue::from_str(&format!(
+            "`{}` must be a non-negative integer",
+            name
+        ))),
+    }
+}
+
+fn flag(entry: &JsValue, name: &str) -> Result<bool, JsValue> {
+    let value = field(entry, name)?;
+    if value.is_undefined() {
+        return Ok(false);
+    }
+    value
+        .as_bool()
+        .ok_or_else(|| JsValue::from_str(&format!("`{}` must be a boolean", name)))
+}
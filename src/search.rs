@@ -0,0 +1,84 @@
+// Searching the universe for occurrences of a pattern.
+
+use wasm_bindgen::prelude::*;
+
+use crate::pattern::Pattern;
+use crate::{Universe, ALIVE};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Find every place where the named pattern appears, in any rotation or
+    /// reflection, wrapping around the edges.
+    ///
+    /// A match requires the pattern's live cells to be alive and every other
+    /// cell in its bounding box to be dead. Returns the top-left row and
+    /// column of each match as a flat `[row0, col0, row1, col1, ...]` array.
+    pub fn find_pattern(&self, name: &str) -> Result<js_sys::Uint32Array, JsValue> {
+        let pattern = self.find_named_pattern(name)?;
+        let matches = self.find_occurrences(&pattern);
+        Ok(js_sys::Uint32Array::from(&matches[..]))
+    }
+}
+
+impl Universe {
+    fn find_occurrences(&self, pattern: &Pattern) -> Vec<u32> {
+        let mut matches = Vec::new();
+        for mask in orientation_masks(pattern) {
+            // A pattern larger than the universe would overlap itself.
+            if mask.width > self.width || mask.height > self.height {
+                continue;
+            }
+            for row in 0..self.height {
+                for col in 0..self.width {
+                    if self.matches_at(&mask, row, col) {
+                        matches.push(row);
+                        matches.push(col);
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    fn matches_at(&self, mask: &Mask, start_row: u32, start_col: u32) -> bool {
+        (0..mask.height).all(|row| {
+            (0..mask.width).all(|col| {
+                let idx = self.offset_index(start_row.into(), start_col.into(), row, col);
+                let alive = idx.is_some_and(|idx| self.cells[idx] == ALIVE);
+                alive == mask.cells[(row * mask.width + col) as usize]
+            })
+        })
+    }
+}
+
+// A pattern's bounding box as a dense grid of live/dead flags.
+#[derive(PartialEq, Eq)]
+struct Mask {
+    width: u32,
+    height: u32,
+    cells: Vec<bool>,
+}
+
+// The distinct orientations of a pattern under rotation and reflection.
+// Symmetric patterns produce fewer than eight.
+fn orientation_masks(pattern: &Pattern) -> Vec<Mask> {
+    let mut masks: Vec<Mask> = Vec::new();
+    for flip in [false, true] {
+        for rotation in 0..4 {
+            let oriented = pattern.transformed(rotation, flip, false);
+            let mut cells = vec![false; (oriented.width * oriented.height) as usize];
+            for &(row, col) in &oriented.cells {
+                cells[(row * oriented.width + col) as usize] = true;
+            }
+            let mask = Mask {
+                width: oriented.width,
+                height: oriented.height,
+                cells,
+            };
+            if !masks.contains(&mask) {
+                masks.push(mask);
+            }
+        }
+    }
+    masks
+}
@@ -0,0 +1,238 @@
+// A rectangle of selected cells that can be cleared, kept or moved.
+//
+// On a torus a selection may run past the right or bottom edge and wrap
+// around to the other side, like a placed pattern, and moving it wraps too.
+// With any other boundary a selection is cut off at the edges, and moving
+// it past them drops whatever crosses and shrinks the selection to the part
+// still on the board.
+
+use wasm_bindgen::prelude::*;
+
+use crate::boundary::Boundary;
+use crate::{PlacementMode, Universe, DEAD};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Selection {
+    row: u32,
+    col: u32,
+    height: u32,
+    width: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Select the `height` by `width` rectangle with its top-left corner at
+    /// (`row`, `col`), replacing any selection. A rectangle larger than the
+    /// universe is shrunk to fit. Throws an error if the corner is outside
+    /// the universe or the rectangle is empty.
+    pub fn select(&mut self, row: u32, col: u32, height: u32, width: u32) -> Result<(), JsValue> {
+        self.checked_index(row, col)?;
+        if height == 0 || width == 0 {
+            return Err(JsValue::from_str(&format!(
+                "Selection must be at least 1x1, not {}x{}",
+                width, height
+            )));
+        }
+        self.selection = self.fitted_selection(i64::from(row), i64::from(col), height, width);
+        Ok(())
+    }
+
+    /// Select nothing.
+    pub fn deselect(&mut self) {
+        self.selection = None;
+    }
+
+    /// The selection as `[row, col, height, width]`, for drawing its
+    /// outline, or `undefined` when nothing is selected. On a torus the
+    /// rectangle may run past the right or bottom edge, continuing on the
+    /// other side.
+    pub fn selection(&self) -> Option<js_sys::Uint32Array> {
+        let selection = self.current_selection()?;
+        Some(js_sys::Uint32Array::from(
+            &[
+                selection.row,
+                selection.col,
+                selection.height,
+                selection.width,
+            ][..],
+        ))
+    }
+
+    /// Clear every selected cell. Returns false, leaving the universe
+    /// unchanged, when nothing is selected.
+    pub fn clear_selection_contents(&mut self) -> bool {
+        let Some(selection) = self.current_selection() else {
+            return false;
+        };
+        self.remember();
+        self.clear_area(
+            selection.row.into(),
+            selection.col.into(),
+            selection.height,
+            selection.width,
+        );
+        true
+    }
+
+    /// Clear every cell outside the selection. Returns false, leaving the
+    /// universe unchanged, when nothing is selected.
+    pub fn delete_outside_selection(&mut self) -> bool {
+        let Some(selection) = self.current_selection() else {
+            return false;
+        };
+        self.remember();
+        let mut keep = vec![false; self.cells.len()];
+        for row in 0..selection.height {
+            for col in 0..selection.width {
+                let idx = self.offset_index(selection.row.into(), selection.col.into(), row, col);
+                if let Some(idx) = idx {
+                    keep[idx] = true;
+                }
+            }
+        }
+        for (idx, keep) in keep.into_iter().enumerate() {
+            if !keep {
+                self.set_state(idx, DEAD);
+            }
+        }
+        true
+    }
+
+    /// Move the selected cells down by `delta_row` rows and right by
+    /// `delta_col` columns, along with the selection itself. Negative
+    /// deltas move up or left. The cells left behind are cleared and those
+    /// moved onto are overwritten, dead or alive. Returns false, leaving
+    /// the universe unchanged, when nothing is selected.
+    pub fn move_selection(&mut self, delta_row: i32, delta_col: i32) -> bool {
+        let Some(selection) = self.current_selection() else {
+            return false;
+        };
+        self.remember();
+        let (row, col) = (i64::from(selection.row), i64::from(selection.col));
+        let moved = self.region_pattern(
+            selection.row,
+            selection.col,
+            selection.height,
+            selection.width,
+        );
+        self.clear_area(row, col, selection.height, selection.width);
+        let (to_row, to_col) = (row + i64::from(delta_row), col + i64::from(delta_col));
+        self.place_at(&moved, to_row, to_col, PlacementMode::Replace);
+        self.selection = self.fitted_selection(to_row, to_col, selection.height, selection.width);
+        true
+    }
+}
+
+impl Universe {
+    // The selection, if it still lies on the board after any resize since
+    // it was made.
+    fn current_selection(&self) -> Option<Selection> {
+        let selection = self.selection?;
+        if selection.row >= self.height || selection.col >= self.width {
+            return None;
+        }
+        self.fitted_selection(
+            selection.row.into(),
+            selection.col.into(),
+            selection.height,
+            selection.width,
+        )
+    }
+
+    // A selection of the given rectangle, wrapped onto the board on a torus
+    // and cut off at the edges otherwise, or `None` if none of it is left.
+    fn fitted_selection(&self, row: i64, col: i64, height: u32, width: u32) -> Option<Selection> {
+        let fit = |start: i64, length: u32, size: u32| {
+            let (length, size) = (i64::from(length), i64::from(size));
+            if self.boundary == Boundary::Torus {
+                return Some((start.rem_euclid(size), length.min(size)));
+            }
+            let (first, last) = (start.max(0), (start + length).min(size));
+            (first < last).then_some((first, last - first))
+        };
+        let (row, height) = fit(row, height, self.height)?;
+        let (col, width) = fit(col, width, self.width)?;
+        Some(Selection {
+            row: row as u32,
+            col: col as u32,
+            height: height as u32,
+            width: width as u32,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+    use crate::testing::{empty, soup};
+    use crate::{Universe, ALIVE, DEAD};
+
+    // The live cells, row by row.
+    fn live(universe: &Universe) -> Vec<(u32, u32)> {
+        (0..universe.height)
+            .flat_map(|row| (0..universe.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| universe.cells[universe.get_index(row, col)] == ALIVE)
+            .collect()
+    }
+
+    fn selection(row: u32, col: u32, height: u32, width: u32) -> Option<Selection> {
+        Some(Selection {
+            row,
+            col,
+            height,
+            width,
+        })
+    }
+
+    #[test]
+    fn selections_wrap_across_both_torus_seams() {
+        // Rows 6, 7, 0 and 1, and columns 8, 9, 0 and 1.
+        let inside = |row: u32, col: u32| !(2..6).contains(&row) && !(2..8).contains(&col);
+        let mut cleared = soup(10, 8, 1.0, 94);
+        cleared.select(6, 8, 4, 4).unwrap();
+        assert_eq!(cleared.current_selection(), selection(6, 8, 4, 4));
+        let mut kept = cleared.clone();
+
+        assert!(cleared.clear_selection_contents());
+        assert!(kept.delete_outside_selection());
+        for row in 0..8 {
+            for col in 0..10 {
+                let idx = cleared.get_index(row, col);
+                assert_eq!(cleared.cells[idx] == DEAD, inside(row, col));
+                assert_eq!(kept.cells[idx] == ALIVE, inside(row, col));
+            }
+        }
+    }
+
+    #[test]
+    fn moving_across_an_edge_wraps_on_a_torus() {
+        let mut universe = empty(10, 8);
+        universe.set_pattern("block", 1, 1).unwrap();
+        // Dead cells moved in clear what they land on.
+        universe.set_pattern("block", 6, 8).unwrap();
+        universe.select(0, 0, 3, 3).unwrap();
+        assert!(universe.move_selection(-3, -2));
+        assert_eq!(universe.current_selection(), selection(5, 8, 3, 3));
+        assert_eq!(live(&universe), [(6, 0), (6, 9), (7, 0), (7, 9)]);
+
+        // Moving round the whole board leaves it where it was.
+        assert!(universe.move_selection(8, 30));
+        assert_eq!(universe.current_selection(), selection(5, 8, 3, 3));
+        assert_eq!(live(&universe), [(6, 0), (6, 9), (7, 0), (7, 9)]);
+    }
+
+    #[test]
+    fn moving_past_a_dead_edge_drops_what_crosses() {
+        let mut universe = empty(10, 8);
+        universe.set_boundary("dead").unwrap();
+        universe.set_pattern("block", 1, 1).unwrap();
+        universe.select(1, 1, 2, 2).unwrap();
+        assert!(universe.move_selection(-2, 0));
+        assert_eq!(universe.current_selection(), selection(0, 1, 1, 2));
+        assert_eq!(live(&universe), [(0, 1), (0, 2)]);
+
+        assert!(universe.move_selection(0, -5));
+        assert_eq!(universe.current_selection(), None);
+        assert!(live(&universe).is_empty());
+    }
+}
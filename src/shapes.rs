@@ -0,0 +1,294 @@
+// Drawing lines, rectangles, ellipses and brush strokes of cells, and
+// flood filling, for drawing tools.
+//
+// Every shape includes its end points and edges. Shapes may extend past the
+// edges of the universe: like placed patterns, they wrap around on a torus
+// and are cut off otherwise.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::{toggled, wireworld, Cell, Universe, ALIVE, DEAD, MAX_CELLS};
+
+// What a brush does to the cells under it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BrushMode {
+    Alive,
+    Dead,
+    Toggle,
+}
+
+impl BrushMode {
+    fn parse(mode: &str) -> Result<BrushMode, JsValue> {
+        match mode.trim().to_lowercase().as_str() {
+            "alive" => Ok(BrushMode::Alive),
+            "dead" => Ok(BrushMode::Dead),
+            "toggle" => Ok(BrushMode::Toggle),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown brush mode '{}'; expected 'alive', 'dead' or 'toggle'",
+                mode.trim()
+            ))),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set the cells along the straight line from (`r0`, `c0`) to
+    /// (`r1`, `c1`), both ends included, to `state`. The line is one cell
+    /// thick, stepping diagonally where needed, and a line from a cell to
+    /// itself sets just that cell. Throws an error if the line would be
+    /// more than 2^28 cells long.
+    pub fn draw_line(
+        &mut self,
+        r0: i32,
+        c0: i32,
+        r1: i32,
+        c1: i32,
+        state: Cell,
+    ) -> Result<(), JsValue> {
+        let (r0, c0, r1, c1) = (i64::from(r0), i64::from(c0), i64::from(r1), i64::from(c1));
+        check_size((r1 - r0).abs().max((c1 - c0).abs()) as u64 + 1)?;
+        self.remember();
+        line((r0, c0), (r1, c1), |row, col| self.plot(row, col, state));
+        Ok(())
+    }
+
+    /// Set the cells of the `height` by `width` rectangle with its top-left
+    /// corner at (`row`, `col`) to `state`, either all of them or, when
+    /// `filled` is false, just its edges. A rectangle with no height or
+    /// width sets nothing. Throws an error if it would cover more than
+    /// 2^28 cells.
+    pub fn draw_rect(
+        &mut self,
+        row: i32,
+        col: i32,
+        height: u32,
+        width: u32,
+        filled: bool,
+        state: Cell,
+    ) -> Result<(), JsValue> {
+        check_size(u64::from(height) * u64::from(width))?;
+        if height == 0 || width == 0 {
+            return Ok(());
+        }
+        self.remember();
+
+        let (top, left) = (i64::from(row), i64::from(col));
+        let (bottom, right) = (top + i64::from(height) - 1, left + i64::from(width) - 1);
+        for r in top..=bottom {
+            if filled || r == top || r == bottom {
+                for c in left..=right {
+                    self.plot(r, c, state);
+                }
+            } else {
+                self.plot(r, left, state);
+                self.plot(r, right, state);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the cells of the ellipse centred on (`center_row`,
+    /// `center_col`) reaching `radius_r` rows above and below the centre
+    /// and `radius_c` columns either side to `state`, either all of them
+    /// or, when `filled` is false, an unbroken outline one cell thick.
+    /// Equal radii draw a circle, and radii of 0 just the centre cell.
+    /// Throws an error if the ellipse's bounding box would cover more than
+    /// 2^28 cells.
+    pub fn draw_ellipse(
+        &mut self,
+        center_row: i32,
+        center_col: i32,
+        radius_r: u32,
+        radius_c: u32,
+        filled: bool,
+        state: Cell,
+    ) -> Result<(), JsValue> {
+        let (radius_r, radius_c) = (i64::from(radius_r), i64::from(radius_c));
+        check_size((2 * radius_r as u64 + 1) * (2 * radius_c as u64 + 1))?;
+        self.remember();
+
+        // How far a row `dy` from the centre reaches either side, or -1 for
+        // rows past the top and bottom. Padding the radii by half a cell
+        // keeps the ends from shrinking to a single cell.
+        let (a, b) = (radius_c as f64 + 0.5, radius_r as f64 + 0.5);
+        let reach = |dy: i64| {
+            if dy.abs() > radius_r {
+                return -1;
+            }
+            let y = dy as f64 / b;
+            ((a * (1.0 - y * y).sqrt()) as i64).min(radius_c)
+        };
+
+        let (row, col) = (i64::from(center_row), i64::from(center_col));
+        for dy in -radius_r..=radius_r {
+            let outer = reach(dy);
+            // An outline cell is one with a neighbour above, below or
+            // beside it outside the ellipse.
+            let inner = if filled {
+                -1
+            } else {
+                reach(dy - 1).min(reach(dy + 1)).min(outer - 1)
+            };
+            for dx in (inner + 1).max(0)..=outer {
+                self.plot(row + dy, col + dx, state);
+                self.plot(row + dy, col - dx, state);
+            }
+        }
+        Ok(())
+    }
+
+    /// Paint every cell within `radius` of (`row`, `col`), measured as a
+    /// straight-line distance so the brush is round: `mode` `"alive"` brings
+    /// them to life, `"dead"` clears them and `"toggle"` flips each between
+    /// alive and dead, as `toggle_cell` does. A radius of 0 paints just the
+    /// one cell. Throws an error if the mode is unknown, the cell is outside
+    /// the universe or the brush would cover more than 2^28 cells.
+    pub fn brush(&mut self, row: u32, col: u32, radius: u32, mode: &str) -> Result<(), JsValue> {
+        self.brush_stroke(&[row, col], radius, mode)
+    }
+
+    /// Paint with the brush at every cell in a flat `[row0, col0, row1,
+    /// col1, ...]` array in one call, such as the points a pointer passed
+    /// through while dragging. Cells the brush covers more than once are
+    /// only toggled once.
+    ///
+    /// All or nothing: if the array has odd length or any point lies
+    /// outside the universe, an error is thrown and no cell is changed.
+    pub fn brush_stroke(&mut self, points: &[u32], radius: u32, mode: &str) -> Result<(), JsValue> {
+        let mode = BrushMode::parse(mode)?;
+        let side = 2 * u64::from(radius) + 1;
+        check_size(side * side)?;
+        if !points.len().is_multiple_of(2) {
+            return Err(JsValue::from_str(&format!(
+                "Points must come in (row, col) pairs, but {} numbers were given",
+                points.len()
+            )));
+        }
+        if let Some(pair) = points
+            .chunks_exact(2)
+            .find(|pair| pair[0] >= self.height || pair[1] >= self.width)
+        {
+            return Err(JsValue::from_str(&format!(
+                "Cell ({}, {}) is outside the {}x{} universe",
+                pair[0], pair[1], self.width, self.height
+            )));
+        }
+        self.remember();
+
+        let radius = i64::from(radius);
+        let mut covered = Vec::new();
+        for pair in points.chunks_exact(2) {
+            let (row, col) = (i64::from(pair[0]), i64::from(pair[1]));
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if dy * dy + dx * dx > radius * radius {
+                        continue;
+                    }
+                    covered.extend(self.offset_index(row + dy, col + dx, 0, 0));
+                }
+            }
+        }
+        covered.sort_unstable();
+        covered.dedup();
+
+        for idx in covered {
+            let state = match mode {
+                BrushMode::Alive => ALIVE,
+                BrushMode::Dead => DEAD,
+                BrushMode::Toggle => self.toggled_at(idx),
+            };
+            self.set_state(idx, state);
+        }
+        Ok(())
+    }
+
+    /// Set the cell at (`row`, `col`) and every cell joined to it through
+    /// cells above, below or beside each other in the same state to
+    /// `state`, like a paint bucket. The region wraps around the edges on a
+    /// torus. Filling a region with the state it already has changes
+    /// nothing. Throws an error if the cell is outside the universe.
+    pub fn flood_fill(&mut self, row: u32, col: u32, state: Cell) -> Result<(), JsValue> {
+        let start = self.checked_index(row, col)?;
+        let (from, to) = (self.state(start), state as u8);
+        if from == to {
+            return Ok(());
+        }
+        self.remember();
+
+        // Cells are filled as they are found, so each is pushed once.
+        self.set_state(start, to);
+        let mut stack = vec![(row, col)];
+        while let Some((row, col)) = stack.pop() {
+            let (r, c) = (i64::from(row), i64::from(col));
+            for (r, c) in [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)] {
+                let Some(r) = self.boundary.place(r, self.height) else {
+                    continue;
+                };
+                let Some(c) = self.boundary.place(c, self.width) else {
+                    continue;
+                };
+                let idx = self.get_index(r, c);
+                if self.state(idx) == from {
+                    self.set_state(idx, to);
+                    stack.push((r, c));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    // The state clicking the cell at `idx` would give it, as `toggle_cell`
+    // does.
+    pub(crate) fn toggled_at(&self, idx: usize) -> u8 {
+        match self.automaton {
+            Automaton::Wireworld => wireworld::toggled(self.state(idx)),
+            _ => toggled(self.state(idx)),
+        }
+    }
+
+    // Set the cell at a position that may lie off the board, wrapping or
+    // leaving it out depending on the boundary.
+    fn plot(&mut self, row: i64, col: i64, state: Cell) {
+        if let Some(idx) = self.offset_index(row, col, 0, 0) {
+            self.set_state(idx, state as u8);
+        }
+    }
+}
+
+// Visit each cell along the line between two cells, both included, with
+// Bresenham's algorithm, stepping along both axes at once.
+pub(crate) fn line((r0, c0): (i64, i64), (r1, c1): (i64, i64), mut visit: impl FnMut(i64, i64)) {
+    let (dr, dc) = ((r1 - r0).abs(), (c1 - c0).abs());
+    let (step_r, step_c) = ((r1 - r0).signum(), (c1 - c0).signum());
+    let (mut row, mut col) = (r0, c0);
+    let mut error = dc - dr;
+    loop {
+        visit(row, col);
+        if (row, col) == (r1, c1) {
+            return;
+        }
+        let doubled = 2 * error;
+        if doubled > -dr {
+            error -= dr;
+            col += step_c;
+        }
+        if doubled < dc {
+            error += dc;
+            row += step_r;
+        }
+    }
+}
+
+fn check_size(cells: u64) -> Result<(), JsValue> {
+    if cells > u64::from(MAX_CELLS) {
+        return Err(JsValue::from_str(&format!(
+            "A shape of {} cells is too large; it may cover at most {}",
+            cells, MAX_CELLS
+        )));
+    }
+    Ok(())
+}
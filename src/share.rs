@@ -0,0 +1,105 @@
+// Short strings describing a whole board, for sharing it in a link.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rules::Rule;
+use crate::{base64, check_dimensions, rle, Universe, ALIVE, DEAD};
+
+#[wasm_bindgen]
+impl Universe {
+    /// The whole board and its rule as URL-safe base64, for the fragment of
+    /// a link; `from_share_string` reads it back.
+    ///
+    /// The board is run-length encoded as RLE first, so mostly empty
+    /// boards give short strings. Only the live cells, size and rule are
+    /// kept, not the generation or dying cells.
+    pub fn to_share_string(&self) -> String {
+        let board = self.region_pattern(0, 0, self.height, self.width);
+        base64::encode_url(rle::encode(&board, &self.rule()).as_bytes())
+    }
+
+    /// A universe from a string made by `to_share_string`. Throws an error
+    /// describing the problem for anything else.
+    pub fn from_share_string(text: &str) -> Result<Universe, JsValue> {
+        parse(text).map_err(|e| JsValue::from_str(&format!("Invalid share string: {}", e)))
+    }
+}
+
+fn parse(text: &str) -> Result<Universe, String> {
+    let bytes = base64::decode_url(text.trim())?;
+    let rle = String::from_utf8(bytes).map_err(|_| "it does not decode to text".to_string())?;
+    // The body can't place cells outside the declared size, so checking it
+    // first bounds everything parsing allocates.
+    let (width, height) = rle::header_size(&rle)?;
+    let size = check_dimensions(width, height)?;
+    let board = rle::parse(&rle)?;
+    let rule = rle::header_rule(&rle).map(Rule::parse).transpose()?;
+
+    let mut cells = vec![DEAD; size];
+    for &(row, col) in &board.cells {
+        cells[(row * board.width + col) as usize] = ALIVE;
+    }
+    let mut universe = Universe::with_cells(board.width, board.height, cells);
+    if let Some(rule) = rule {
+        universe.apply_rule(rule);
+    }
+    Ok(universe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::testing::{empty, soup};
+    use crate::{base64, pattern};
+
+    #[test]
+    fn random_boards_round_trip() {
+        for seed in 0..50 {
+            let (width, height) = (1 + seed as u32 * 7 % 90, 1 + seed as u32 * 13 % 70);
+            let mut universe = soup(width, height, 0.3, seed);
+            if seed % 2 == 0 {
+                universe.set_rule("B36/S23").unwrap();
+            }
+            let shared = parse(&universe.to_share_string()).unwrap();
+            assert_eq!((shared.width, shared.height), (width, height));
+            assert_eq!(shared.cells, universe.cells, "seed {seed}");
+            assert_eq!(shared.rule, universe.rule);
+        }
+    }
+
+    #[test]
+    fn builtin_patterns_round_trip() {
+        for name in pattern::builtin_names() {
+            let board = pattern::builtin(name).unwrap();
+            let mut universe = empty(board.width + 2, board.height + 2);
+            universe.set_pattern(name, 1, 1).unwrap();
+            let shared = parse(&universe.to_share_string()).unwrap();
+            assert_eq!(shared.cells, universe.cells, "{name}");
+        }
+    }
+
+    #[test]
+    fn oversized_boards_are_rejected_before_parsing() {
+        // Parsing the body would list four billion cells.
+        let rle = "x = 4000000000, y = 1\n4000000000o!";
+        let error = parse(&base64::encode_url(rle.as_bytes())).err().unwrap();
+        assert!(error.contains("4000000000x1"), "{error}");
+    }
+
+    #[test]
+    fn junk_gives_errors() {
+        for text in ["", "!!!", "%%%%", "\u{1F600}"] {
+            assert!(parse(text).is_err(), "{text}");
+        }
+        assert!(parse(&base64::encode_url(&[0xff, 0xfe])).is_err());
+        for rle in [
+            "x = 3\n!",
+            "x = 1, y = 1\noo!",
+            "x = 0, y = 0\n!",
+            "x = 2, y = 2, rule = Q\n!",
+            "x = 2, y = 2\n2o3",
+        ] {
+            assert!(parse(&base64::encode_url(rle.as_bytes())).is_err(), "{rle}");
+        }
+    }
+}
@@ -0,0 +1,359 @@
+// A compact binary image of the whole universe, for saving and restoring it.
+//
+// The layout, with integers little-endian:
+//
+//   magic        4 bytes   "GOLS"
+//   version      1 byte    2
+//   width        u32
+//   height       u32
+//   generation   u64
+//   rule         1-byte length, then the B/S rulestring
+//   automaton    1 byte    0 Life, 1 Wireworld, 2 Immigration, 3 elementary,
+//                          4 Larger than Life, 5 MAP
+//   parameters   the elementary rule number as a byte, the Larger than
+//                Life radius and birth and survival ranges as five u32s, or
+//                the 64 bytes of a MAP rule's table; nothing otherwise
+//   packing      1 byte    0 for a bit per cell, 1 for a byte per cell
+//   cells        the rest, in row-major order
+//
+// Version 1 has no automaton or parameters and is always Life. Future
+// versions may add fields, but must keep reading versions 1 and 2.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::ltl::LtlRule;
+use crate::map_rule::MapRule;
+use crate::rules::Rule;
+use crate::undo::SavedBoard;
+use crate::{check_dimensions, Universe};
+
+const MAGIC: &[u8; 4] = b"GOLS";
+const VERSION: u8 = 2;
+
+#[wasm_bindgen]
+impl Universe {
+    /// The size, rule and automaton, generation and cells as a compact
+    /// binary image that `restore` and `from_snapshot` read back. Cells take
+    /// a bit each unless some are in a third state, such as dying cells
+    /// under a Generations rule or Wireworld's wires. A custom rule isn't
+    /// saved.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let board = self.save_board();
+        let rule = self.rule.to_string();
+
+        let mut data = Vec::with_capacity(32 + rule.len() + board.data.len());
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.extend_from_slice(&board.width.to_le_bytes());
+        data.extend_from_slice(&board.height.to_le_bytes());
+        data.extend_from_slice(&board.generation.to_le_bytes());
+        data.push(rule.len() as u8);
+        data.extend_from_slice(rule.as_bytes());
+        write_automaton(&mut data, self.automaton);
+        data.push(if board.packed { 0 } else { 1 });
+        data.extend_from_slice(&board.data);
+        data
+    }
+
+    /// Replace the whole universe with one saved by `snapshot`. Throws an
+    /// error, leaving the universe unchanged, if the data is truncated,
+    /// corrupt or from an unknown version.
+    pub fn restore(&mut self, data: &[u8]) -> Result<(), JsValue> {
+        self.restore_snapshot(data)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// A universe restored from a `snapshot`, as with `restore`.
+    pub fn from_snapshot(data: &[u8]) -> Result<Universe, JsValue> {
+        let mut universe = Universe::new();
+        universe.restore(data)?;
+        Ok(universe)
+    }
+}
+
+impl Universe {
+    // `restore`, with the error as a string.
+    pub(crate) fn restore_snapshot(&mut self, data: &[u8]) -> Result<(), String> {
+        let (rule, automaton, board) = parse(data)?;
+        self.remember();
+        self.apply_rule(rule);
+        if automaton != Automaton::Life {
+            self.apply_automaton(automaton, automaton.states(rule));
+        }
+        self.load_board(&board);
+        Ok(())
+    }
+}
+
+pub fn parse(data: &[u8]) -> Result<(Rule, Automaton, SavedBoard), String> {
+    let mut reader = Reader { data };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err("Not a universe snapshot".to_string());
+    }
+    let version = reader.byte()?;
+    if !(1..=VERSION).contains(&version) {
+        return Err(format!(
+            "Snapshot version {} is not supported; expected 1 to {}",
+            version, VERSION
+        ));
+    }
+
+    let width = u32::from_le_bytes(reader.array()?);
+    let height = u32::from_le_bytes(reader.array()?);
+    let generation = u64::from_le_bytes(reader.array()?);
+    let size = check_dimensions(width, height)?;
+
+    let rule_len = usize::from(reader.byte()?);
+    let rule = std::str::from_utf8(reader.take(rule_len)?)
+        .map_err(|_| "Snapshot rule is not valid text".to_string())
+        .and_then(Rule::parse)?;
+    let automaton = if version >= 2 {
+        read_automaton(&mut reader)?
+    } else {
+        Automaton::Life
+    };
+
+    let packed = match reader.byte()? {
+        0 => true,
+        1 => false,
+        other => return Err(format!("Unknown cell packing {} in snapshot", other)),
+    };
+    let expected = if packed { size.div_ceil(8) } else { size };
+    let cells = reader.take(expected)?;
+    if !reader.data.is_empty() {
+        return Err(format!(
+            "Snapshot has {} unexpected bytes at the end",
+            reader.data.len()
+        ));
+    }
+    let states = automaton.states(rule);
+    if !packed {
+        if let Some(state) = cells.iter().find(|&&state| state >= states) {
+            return Err(format!(
+                "Snapshot holds cell state {}, but its automaton only has {} states",
+                state, states
+            ));
+        }
+    }
+
+    let board = SavedBoard {
+        width,
+        height,
+        generation,
+        packed,
+        data: cells.to_vec(),
+    };
+    Ok((rule, automaton, board))
+}
+
+fn write_automaton(data: &mut Vec<u8>, automaton: Automaton) {
+    match automaton {
+        Automaton::Life => data.push(0),
+        Automaton::Wireworld => data.push(1),
+        Automaton::Immigration => data.push(2),
+        Automaton::Elementary(rule) => data.extend([3, rule]),
+        Automaton::LargerThanLife(rule) => {
+            data.push(4);
+            for value in [
+                rule.radius,
+                rule.birth_min,
+                rule.birth_max,
+                rule.survive_min,
+                rule.survive_max,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        Automaton::Map(rule) => {
+            data.push(5);
+            data.extend_from_slice(&rule.table);
+        }
+    }
+}
+
+fn read_automaton(reader: &mut Reader) -> Result<Automaton, String> {
+    Ok(match reader.byte()? {
+        0 => Automaton::Life,
+        1 => Automaton::Wireworld,
+        2 => Automaton::Immigration,
+        3 => Automaton::Elementary(reader.byte()?),
+        4 => {
+            let mut value = || reader.array().map(u32::from_le_bytes);
+            let rule = LtlRule {
+                radius: value()?,
+                birth_min: value()?,
+                birth_max: value()?,
+                survive_min: value()?,
+                survive_max: value()?,
+            };
+            rule.check()?;
+            Automaton::LargerThanLife(rule)
+        }
+        5 => Automaton::Map(MapRule {
+            table: reader.array()?,
+        }),
+        other => return Err(format!("Unknown automaton {} in snapshot", other)),
+    })
+}
+
+// Reads a snapshot from the front, failing cleanly when it runs out.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < len {
+            return Err("Snapshot is truncated".to_string());
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map_rule::MapRule;
+    use crate::neighborhood::Neighborhood;
+    use crate::testing::soup;
+    use crate::ALIVE;
+
+    // Restore a snapshot of `universe` into a fresh universe and check it
+    // holds the same board and carries on exactly as the original does.
+    fn check_round_trip(mut universe: Universe) {
+        let mut restored = Universe::from_snapshot(&universe.snapshot()).unwrap();
+        assert_eq!(restored.automaton, universe.automaton);
+        assert_eq!(restored.rule, universe.rule);
+        assert_eq!(restored.generation, universe.generation);
+        assert_eq!(
+            (restored.width, restored.height),
+            (universe.width, universe.height)
+        );
+        assert_eq!(restored.cells, universe.cells);
+        for _ in 0..5 {
+            universe.tick();
+            restored.tick();
+            assert_eq!(restored.cells, universe.cells);
+        }
+    }
+
+    #[test]
+    fn life_round_trips() {
+        let mut universe = soup(40, 30, 0.3, 1);
+        universe.tick_n(3);
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn generations_rule_round_trips() {
+        let mut universe = soup(40, 30, 0.3, 2);
+        universe.set_automaton("brians_brain").unwrap();
+        universe.tick_n(3);
+        assert!(universe.cells.iter().any(|&state| state > ALIVE));
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn wireworld_round_trips() {
+        let mut universe = soup(40, 30, 0.0, 3);
+        universe.set_automaton("wireworld").unwrap();
+        universe.set_pattern("clock", 2, 2).unwrap();
+        universe.set_pattern("diode", 10, 2).unwrap();
+        universe.tick_n(2);
+        assert!(universe.cells.contains(&3));
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn immigration_round_trips() {
+        let mut universe = soup(40, 30, 0.0, 4);
+        universe.set_automaton("immigration").unwrap();
+        for (row, col, player) in [(5, 5, 1), (5, 6, 2), (5, 7, 1), (9, 9, 2), (9, 10, 2)] {
+            universe.toggle_cell_for_player(row, col, player).unwrap();
+        }
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn elementary_round_trips() {
+        let mut universe = soup(40, 30, 0.0, 5);
+        universe.set_elementary_rule(30);
+        universe.toggle_cell(0, 20);
+        universe.tick_n(4);
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn larger_than_life_round_trips() {
+        let mut universe = soup(40, 30, 0.5, 6);
+        universe.set_rule_ltl(5, 34, 45, 34, 58).unwrap();
+        universe.tick_n(2);
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn map_round_trips() {
+        let mut universe = soup(40, 30, 0.3, 7);
+        let highlife = Rule::parse("B36/S23").unwrap();
+        let map = MapRule::from_rule(highlife, Neighborhood::Moore);
+        universe.set_rule_map(&map.to_string()).unwrap();
+        universe.tick_n(2);
+        check_round_trip(universe);
+    }
+
+    #[test]
+    fn version_one_restores_as_life() {
+        let mut universe = soup(20, 10, 0.3, 8);
+        universe.tick_n(2);
+        let mut data = universe.snapshot();
+        // Version 1 had no automaton byte, which follows the rule.
+        data[4] = 1;
+        let rule_end = 4 + 1 + 4 + 4 + 8 + 1 + usize::from(data[21]);
+        assert_eq!(data.remove(rule_end), 0);
+
+        let restored = Universe::from_snapshot(&data).unwrap();
+        assert_eq!(restored.automaton, Automaton::Life);
+        assert_eq!(restored.cells, universe.cells);
+        assert_eq!(restored.generation, universe.generation);
+    }
+
+    #[test]
+    fn states_are_checked_against_the_automaton() {
+        let mut universe = soup(20, 10, 0.0, 9);
+        universe.set_automaton("wireworld").unwrap();
+        universe.set_pattern("clock", 2, 2).unwrap();
+        let mut data = universe.snapshot();
+        assert!(parse(&data).is_ok());
+
+        // The same cells claimed to be a Life board.
+        let automaton = 4 + 1 + 4 + 4 + 8 + 1 + usize::from(data[21]);
+        assert_eq!(data[automaton], 1);
+        data[automaton] = 0;
+        assert!(parse(&data).unwrap_err().contains("only has 2 states"));
+    }
+
+    #[test]
+    fn truncated_parameters_are_rejected() {
+        let mut universe = soup(20, 10, 0.3, 10);
+        universe.set_rule_ltl(2, 3, 5, 3, 6).unwrap();
+        let data = universe.snapshot();
+        let automaton = 4 + 1 + 4 + 4 + 8 + 1 + usize::from(data[21]);
+        for len in automaton..automaton + 21 {
+            assert!(parse(&data[..len]).unwrap_err().contains("truncated"));
+        }
+        assert!(parse(&data).is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+// Searching random soups for boards that settle into something worth a
+// closer look.
+//
+// Each trial fills a scratch copy of the universe, with the same size, rule
+// and boundary, from its own seed, so any trial can be made again on the
+// live board with `set_seed` and `randomize_with_density`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::random::check_density;
+use crate::Universe;
+
+// The longest period a soup is checked for settling into; enough for the
+// common oscillators left behind by soups, up to the pentadecathlon.
+const SOUP_MAX_PERIOD: u32 = 15;
+
+// The most cell updates one search may run over all its trials, so it
+// can't freeze the page for long.
+const MAX_SOUP_WORK: u64 = 1 << 30;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Run `trials` random soups, each a board the size of this one filled
+    /// as `randomize_with_density` would, under the same rule and boundary,
+    /// for up to `generations` generations or until it dies out or cycles
+    /// with a period of at most 15. Returns an array with an object per
+    /// trial holding:
+    ///
+    /// - `seed`, a `BigInt` to pass to `set_seed` before
+    ///   `randomize_with_density` to make the soup again
+    /// - `population`, the live cells once it stopped
+    /// - `settled_at`, the generation it died out or its cycle began, or
+    ///   `null` if it was still changing
+    /// - `period`, as `detect_period` reports it: -1 for a dead board, 0 if
+    ///   it was still changing, or the period of its cycle
+    ///
+    /// The board is left alone; only the random number generator moves on,
+    /// as the seeds are drawn from it, so setting its seed first makes the
+    /// whole search repeatable. Throws an error unless `density` is between
+    /// 0 and 1, or if the search would update more than 2^30 cells in all.
+    pub fn run_soup_search(
+        &mut self,
+        trials: u32,
+        generations: u32,
+        density: f64,
+    ) -> Result<js_sys::Array, JsValue> {
+        check_density(density)?;
+        let work = u64::from(trials) * u64::from(generations) * self.cells.len() as u64;
+        if work > MAX_SOUP_WORK {
+            return Err(JsValue::from_str(&format!(
+                "{} trials of {} generations on {} cells is too many; they may update at most {} cells",
+                trials,
+                generations,
+                self.cells.len(),
+                MAX_SOUP_WORK
+            )));
+        }
+
+        let mut scratch = self.scratch_copy();
+        scratch.ants.clear();
+        let results = js_sys::Array::new();
+        for _ in 0..trials {
+            let seed = self.rng.next_u64();
+            scratch.set_seed(seed);
+            scratch.randomize_with_density(density)?;
+            scratch.generation = 0;
+
+            let (settled_at, period) = match scratch.tick_until_stable(generations, SOUP_MAX_PERIOD)
+            {
+                Ok(ran) => {
+                    let period = scratch.detect_period(SOUP_MAX_PERIOD);
+                    let settled_at = ran - period.max(0) as u32;
+                    (JsValue::from(settled_at), period)
+                }
+                Err(_) => (JsValue::NULL, 0),
+            };
+
+            let record = js_sys::Object::new();
+            for (key, value) in [
+                ("seed", JsValue::from(seed)),
+                ("population", JsValue::from(scratch.population())),
+                ("settled_at", settled_at),
+                ("period", JsValue::from(period)),
+            ] {
+                let _ = js_sys::Reflect::set(&record, &key.into(), &value);
+            }
+            results.push(&record);
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,276 @@
+// A universe that stores only its live cells, for huge boards that are
+// mostly empty.
+//
+// Each tick counts neighbours only around the live cells, so the cost
+// follows the population rather than the area. On a 512x512 board this
+// beats the dense `Universe` while fewer than about 1% of the cells are
+// alive; past that the dense grid wins. `cargo bench --bench sparse`
+// measures the crossover.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::pattern::{self, Pattern};
+use crate::rules::Rule;
+use crate::{check_dimensions, Universe, ALIVE, DEAD};
+
+/// A Life-like universe kept as a set of live cells, with the edges joined
+/// as a torus.
+///
+/// It supports two-state B/S rules without `B0`, which would bring the
+/// whole empty board to life at once.
+#[wasm_bindgen]
+pub struct SparseUniverse {
+    width: u32,
+    height: u32,
+    live: HashSet<(u32, u32)>,
+    rule: Rule,
+}
+
+#[wasm_bindgen]
+impl SparseUniverse {
+    /// An empty universe running Conway's Life. Throws an error if either
+    /// dimension is zero.
+    pub fn new(width: u32, height: u32) -> Result<SparseUniverse, JsValue> {
+        if width == 0 || height == 0 {
+            return Err(JsValue::from_str(&format!(
+                "Universe must be at least 1x1, not {}x{}",
+                width, height
+            )));
+        }
+        Ok(SparseUniverse {
+            width,
+            height,
+            live: HashSet::new(),
+            rule: Rule::CONWAY,
+        })
+    }
+
+    /// A sparse copy of a dense universe. Throws an error unless it runs a
+    /// two-state Life-like rule; dying cells are left out.
+    pub fn from_dense(universe: &Universe) -> Result<SparseUniverse, JsValue> {
+        if universe.automaton != Automaton::Life || universe.custom_rule.is_some() {
+            return Err(JsValue::from_str(
+                "Only universes running a Life-like rule can be made sparse",
+            ));
+        }
+        let mut sparse = SparseUniverse::new(universe.width, universe.height)?;
+        sparse.apply_rule(universe.rule)?;
+        let width = universe.width as usize;
+        sparse.live = (0..universe.cells.len())
+            .filter(|&idx| universe.cells[idx] == ALIVE)
+            .map(|idx| ((idx / width) as u32, (idx % width) as u32))
+            .collect();
+        Ok(sparse)
+    }
+
+    /// A dense copy of this universe with the same rule. Throws an error if
+    /// the board is too large to hold every cell.
+    pub fn to_dense(&self) -> Result<Universe, JsValue> {
+        let size = check_dimensions(self.width, self.height).map_err(|e| JsValue::from_str(&e))?;
+        let mut cells = vec![DEAD; size];
+        for &(row, col) in &self.live {
+            cells[(row * self.width + col) as usize] = ALIVE;
+        }
+        let mut universe = Universe::with_cells(self.width, self.height, cells);
+        universe.apply_rule(self.rule);
+        Ok(universe)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of live cells.
+    pub fn population(&self) -> u32 {
+        self.live.len() as u32
+    }
+
+    /// Every live cell as a flat `[row0, col0, row1, col1, ...]` array, in
+    /// row-major order.
+    pub fn live_cells(&self) -> js_sys::Uint32Array {
+        let mut live: Vec<(u32, u32)> = self.live.iter().copied().collect();
+        live.sort_unstable();
+        let flat: Vec<u32> = live.into_iter().flat_map(|(row, col)| [row, col]).collect();
+        js_sys::Uint32Array::from(&flat[..])
+    }
+
+    /// Change the rule to a B/S rulestring such as `B36/S23`, as with
+    /// `Universe::set_rule`. Generations rules and rules with `B0` are
+    /// rejected.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        let rule = Rule::parse(rule).map_err(|e| JsValue::from_str(&e))?;
+        self.apply_rule(rule)
+    }
+
+    /// The current rule as a canonical B/S rulestring.
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        let cell = (row % self.height, column % self.width);
+        if !self.live.remove(&cell) {
+            self.live.insert(cell);
+        }
+    }
+
+    /// Place a built-in pattern with its top-left corner at the given
+    /// position, clearing its bounding box first and wrapping at the edges,
+    /// as with `Universe::set_pattern`.
+    pub fn set_pattern(
+        &mut self,
+        pattern: &str,
+        start_row: u32,
+        start_col: u32,
+    ) -> Result<(), JsValue> {
+        let found = pattern::builtin(pattern).ok_or_else(|| {
+            let known: Vec<String> = pattern::builtin_names().map(String::from).collect();
+            JsValue::from_str(&pattern::unknown_name(pattern, &known))
+        })?;
+        self.place(&found, start_row, start_col);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.live.clear();
+    }
+
+    pub fn tick(&mut self) {
+        // Live neighbours of every cell next to a live cell; any cell not
+        // listed has none.
+        let mut counts: HashMap<(u32, u32), u8> = HashMap::with_capacity(self.live.len() * 8);
+        for &(row, col) in &self.live {
+            for delta_row in -1..=1 {
+                for delta_col in -1..=1 {
+                    if (delta_row, delta_col) != (0, 0) {
+                        let neighbor = (
+                            wrap(row, delta_row, self.height),
+                            wrap(col, delta_col, self.width),
+                        );
+                        *counts.entry(neighbor).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let next = |cell: &(u32, u32), count: u8| {
+            let state = if self.live.contains(cell) {
+                ALIVE
+            } else {
+                DEAD
+            };
+            self.rule.next(state, count) == ALIVE
+        };
+        let mut live: HashSet<(u32, u32)> = counts
+            .iter()
+            .filter(|&(cell, &count)| next(cell, count))
+            .map(|(&cell, _)| cell)
+            .collect();
+        // Live cells with no live neighbours survive only under S0.
+        live.extend(
+            self.live
+                .iter()
+                .filter(|cell| !counts.contains_key(cell) && next(cell, 0)),
+        );
+        self.live = live;
+    }
+
+    /// Render the `height` by `width` rectangle with its top-left corner at
+    /// (`row`, `col`), one line per row, wrapping at the edges.
+    pub fn render_viewport(&self, row: u32, col: u32, height: u32, width: u32) -> String {
+        let mut text = String::new();
+        for r in 0..height {
+            for c in 0..width {
+                let cell = (
+                    wrap(row, r.into(), self.height),
+                    wrap(col, c.into(), self.width),
+                );
+                text.push(if self.live.contains(&cell) {
+                    '◼'
+                } else {
+                    '◻'
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl SparseUniverse {
+    fn apply_rule(&mut self, rule: Rule) -> Result<(), JsValue> {
+        if rule.states != 2 {
+            return Err(JsValue::from_str(&format!(
+                "Sparse universes only support two-state rules, not {}",
+                rule
+            )));
+        }
+        if rule.birth & 1 != 0 {
+            return Err(JsValue::from_str(&format!(
+                "Sparse universes can't run B0 rules such as {}",
+                rule
+            )));
+        }
+        self.rule = rule;
+        Ok(())
+    }
+
+    fn place(&mut self, pattern: &Pattern, start_row: u32, start_col: u32) {
+        for row in 0..pattern.height {
+            for col in 0..pattern.width {
+                self.live.remove(&(
+                    wrap(start_row, row.into(), self.height),
+                    wrap(start_col, col.into(), self.width),
+                ));
+            }
+        }
+        for &(row, col) in &pattern.cells {
+            self.live.insert((
+                wrap(start_row, row.into(), self.height),
+                wrap(start_col, col.into(), self.width),
+            ));
+        }
+    }
+}
+
+// The position `offset` cells on from `position` along an axis of `size`
+// cells, wrapping around.
+fn wrap(position: u32, offset: i64, size: u32) -> u32 {
+    (i64::from(position) + offset).rem_euclid(i64::from(size)) as u32
+}
+
+impl fmt::Display for SparseUniverse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render_viewport(0, 0, self.height, self.width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseUniverse;
+    use crate::testing::soup;
+
+    #[test]
+    fn viewports_match_the_dense_universe() {
+        let dense = soup(20, 12, 0.3, 48);
+        let sparse = SparseUniverse::from_dense(&dense).unwrap();
+        for (row, col, height, width) in [(0, 0, 12, 20), (3, 5, 2, 9), (10, 17, 6, 4)] {
+            assert_eq!(
+                sparse.render_viewport(row, col, height, width),
+                dense.render_viewport_wrapped(row, col, height, width)
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// Saving the universe in the browser's localStorage, by hand or every few
+// ticks.
+//
+// Each entry is a `snapshot` as standard base64, since localStorage only
+// holds strings.
+
+use wasm_bindgen::prelude::*;
+
+use crate::fetch::describe;
+use crate::{base64, Universe};
+
+// Where and how often `tick` saves the universe.
+#[derive(Clone, Debug)]
+pub struct Autosave {
+    key: String,
+    every: u32,
+    // Generations run since the last save.
+    pending: u32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Save the universe to localStorage under `key`, replacing anything
+    /// already there. Throws an error if storage is disabled, as in some
+    /// private browsing modes, or full.
+    pub fn save_to_storage(&self, key: &str) -> Result<(), JsValue> {
+        local_storage()?.set_item(key, &self.stored()).map_err(|e| {
+            let reason = if error_name(&e).as_deref() == Some("QuotaExceededError") {
+                "storage is full".to_string()
+            } else {
+                describe(&e)
+            };
+            JsValue::from_str(&format!("Could not save to '{}': {}", key, reason))
+        })
+    }
+
+    /// A universe saved to localStorage by `save_to_storage` or autosave.
+    /// Throws an error if storage is disabled, nothing is saved under `key`
+    /// or what is there isn't a saved universe.
+    pub fn load_from_storage(key: &str) -> Result<Universe, JsValue> {
+        let data = local_storage()?
+            .get_item(key)
+            .map_err(|e| JsValue::from_str(&describe(&e)))?
+            .ok_or_else(|| JsValue::from_str(&format!("Nothing is saved under '{}'", key)))?;
+        Universe::from_stored(&data).map_err(|e| {
+            JsValue::from_str(&format!("'{}' does not hold a saved universe: {}", key, e))
+        })
+    }
+
+    /// Save to localStorage under `key` after every `every_n_ticks` generations
+    /// run by `tick`, `tick_n` or `tick_until_stable`, replacing any earlier
+    /// autosave. Throws an error if storage is disabled or `every_n_ticks` is
+    /// 0.
+    ///
+    /// If a save fails, such as when storage fills up, the error is logged
+    /// to the console and autosave turns itself off.
+    pub fn enable_autosave(&mut self, key: &str, every_n_ticks: u32) -> Result<(), JsValue> {
+        if every_n_ticks == 0 {
+            return Err(JsValue::from_str("every_n_ticks must be at least 1"));
+        }
+        local_storage()?;
+        self.autosave = Some(Autosave {
+            key: key.to_string(),
+            every: every_n_ticks,
+            pending: 0,
+        });
+        Ok(())
+    }
+
+    /// Stop saving automatically. What was last saved stays in storage.
+    pub fn disable_autosave(&mut self) {
+        self.autosave = None;
+    }
+}
+
+impl Universe {
+    // The universe as it is saved in localStorage.
+    fn stored(&self) -> String {
+        base64::encode(&self.snapshot())
+    }
+
+    // A universe read back from what `stored` saved.
+    fn from_stored(data: &str) -> Result<Universe, String> {
+        let snapshot = base64::decode(data)?;
+        let mut universe = Universe::new();
+        universe.restore_snapshot(&snapshot)?;
+        Ok(universe)
+    }
+
+    // Count generations just run towards the next autosave, saving once
+    // enough have passed.
+    pub(crate) fn autosave_after(&mut self, ran: u32) {
+        let Some(autosave) = &mut self.autosave else {
+            return;
+        };
+        autosave.pending = autosave.pending.saturating_add(ran);
+        if autosave.pending < autosave.every {
+            return;
+        }
+        autosave.pending = 0;
+
+        let key = autosave.key.clone();
+        if let Err(error) = self.save_to_storage(&key) {
+            web_sys::console::error_2(&"Autosave failed, turning it off:".into(), &error);
+            self.autosave = None;
+        }
+    }
+}
+
+fn local_storage() -> Result<web_sys::Storage, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window available"))?;
+    window
+        .local_storage()
+        .ok()
+        .flatten()
+        .ok_or_else(|| JsValue::from_str("localStorage is not available"))
+}
+
+// The `name` of a DOMException or other JavaScript error.
+fn error_name(error: &JsValue) -> Option<String> {
+    js_sys::Reflect::get(error, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::soup;
+    use crate::Universe;
+
+    // Read back what would be saved and check it matches.
+    fn check_round_trip(universe: &Universe) {
+        let loaded = Universe::from_stored(&universe.stored()).unwrap();
+        assert_eq!(loaded.automaton, universe.automaton);
+        assert_eq!(loaded.rule, universe.rule);
+        assert_eq!(loaded.generation, universe.generation);
+        assert_eq!(
+            (loaded.width, loaded.height),
+            (universe.width, universe.height)
+        );
+        assert_eq!(loaded.cells, universe.cells);
+    }
+
+    #[test]
+    fn life_session_round_trips() {
+        let mut universe = soup(30, 20, 0.3, 1);
+        universe.tick_n(5);
+        check_round_trip(&universe);
+    }
+
+    #[test]
+    fn wireworld_session_round_trips() {
+        let mut universe = soup(30, 20, 0.0, 2);
+        universe.set_automaton("wireworld").unwrap();
+        universe.set_pattern("clock", 2, 2).unwrap();
+        universe.tick_n(3);
+        check_round_trip(&universe);
+    }
+
+    #[test]
+    fn larger_than_life_session_round_trips() {
+        let mut universe = soup(30, 20, 0.5, 3);
+        universe.set_rule_ltl(2, 3, 5, 3, 6).unwrap();
+        universe.tick_n(2);
+        check_round_trip(&universe);
+    }
+
+    #[test]
+    fn junk_is_rejected() {
+        for junk in ["", "not base64!", "R09MUw==", "AAAAAAAA"] {
+            assert!(Universe::from_stored(junk).is_err(), "{:?}", junk);
+        }
+    }
+}
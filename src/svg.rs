@@ -0,0 +1,109 @@
+// Exporting the board as an SVG image.
+//
+// Live cells are drawn as a single path, with each run of live cells along
+// a row merged into one rectangle, over a background rectangle in the dead
+// color. Grid lines are one unit wide and drawn over the cell edges.
+
+use std::fmt::Write;
+
+use wasm_bindgen::prelude::*;
+
+use crate::draw::DEFAULT_GRID_COLOR;
+use crate::{Universe, ALIVE};
+
+#[wasm_bindgen]
+impl Universe {
+    /// The board as a standalone SVG document, with cells `cell_size` units
+    /// square. Pass an empty `dead_color` to leave the background
+    /// transparent. Grid lines use the color set with `set_grid_lines`, or
+    /// dark grey while they are off. Dying cells are drawn as dead.
+    pub fn to_svg(
+        &self,
+        cell_size: u32,
+        alive_color: &str,
+        dead_color: &str,
+        grid: bool,
+    ) -> String {
+        let size = u64::from(cell_size);
+        let (width, height) = (u64::from(self.width) * size, u64::from(self.height) * size);
+        let mut svg = String::new();
+        // Writing to a string can't fail.
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" shape-rendering="crispEdges">"#,
+            width, height
+        );
+        if !dead_color.is_empty() {
+            let _ = writeln!(
+                svg,
+                r#"<rect width="{}" height="{}" fill="{}"/>"#,
+                width,
+                height,
+                escape(dead_color)
+            );
+        }
+
+        let mut cells = String::new();
+        for (row, states) in self.cells.chunks_exact(self.width as usize).enumerate() {
+            let mut col = 0;
+            while col < states.len() {
+                if states[col] != ALIVE {
+                    col += 1;
+                    continue;
+                }
+                let start = col;
+                while col < states.len() && states[col] == ALIVE {
+                    col += 1;
+                }
+                let run = (col - start) as u64 * size;
+                let _ = write!(
+                    cells,
+                    "M{} {}h{}v{}h-{}z",
+                    start as u64 * size,
+                    row as u64 * size,
+                    run,
+                    size,
+                    run
+                );
+            }
+        }
+        if !cells.is_empty() {
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}" fill="{}"/>"#,
+                cells,
+                escape(alive_color)
+            );
+        }
+
+        if grid {
+            let mut lines = String::new();
+            for col in 0..=u64::from(self.width) {
+                let _ = write!(lines, "M{} 0V{}", col * size, height);
+            }
+            for row in 0..=u64::from(self.height) {
+                let _ = write!(lines, "M0 {}H{}", row * size, width);
+            }
+            let color = self
+                .draw_style
+                .grid
+                .as_deref()
+                .unwrap_or(DEFAULT_GRID_COLOR);
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+                lines,
+                escape(color)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// Escape text for an attribute value in double quotes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
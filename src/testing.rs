@@ -0,0 +1,54 @@
+// Helpers shared by the unit tests.
+
+use crate::rng::Rng;
+use crate::{Universe, ALIVE, DEAD};
+
+// An empty `width` by `height` torus running Conway's Life.
+pub fn empty(width: u32, height: u32) -> Universe {
+    Universe::with_cells(width, height, vec![DEAD; (width * height) as usize])
+}
+
+// A `width` by `height` torus filled at random from `seed`, with about
+// `density` of its cells alive.
+pub fn soup(width: u32, height: u32, density: f64, seed: u64) -> Universe {
+    let mut rng = Rng::new(seed);
+    let cells = (0..width * height)
+        .map(|_| {
+            if rng.next_f64() < density {
+                ALIVE
+            } else {
+                DEAD
+            }
+        })
+        .collect();
+    Universe::with_cells(width, height, cells)
+}
+
+// The next generation of a B3/S23 torus, counting every neighbour afresh
+// with plain modulo arithmetic and none of the tick loop's shortcuts.
+pub fn reference_step(cells: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut next = vec![DEAD; cells.len()];
+    for row in 0..height {
+        for col in 0..width {
+            let mut live = 0;
+            for delta_row in [height - 1, 0, 1] {
+                for delta_col in [width - 1, 0, 1] {
+                    if (delta_row, delta_col) == (0, 0) {
+                        continue;
+                    }
+                    let r = (row + delta_row) % height;
+                    let c = (col + delta_col) % width;
+                    live += u8::from(cells[(r * width + c) as usize] == ALIVE);
+                }
+            }
+            let idx = (row * width + col) as usize;
+            let alive = cells[idx] == ALIVE;
+            next[idx] = if live == 3 || (alive && live == 2) {
+                ALIVE
+            } else {
+                DEAD
+            };
+        }
+    }
+    next
+}
@@ -0,0 +1,100 @@
+// Parsing boards back from the text produced by `render()`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{check_dimensions, Universe, ALIVE, DEAD};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Build a universe from a text diagram such as the output of `render()`.
+    ///
+    /// Live cells may be written as `◼`, `#` or `O` and dead cells as `◻` or
+    /// `.`. The width comes from the first line and the height from the
+    /// number of lines; every line must have the same length.
+    ///
+    /// Only plain output reads back: not the border from
+    /// `set_render_border`, glyphs other than these set with
+    /// `set_render_glyphs`, or the `▣` of dying cells, which need a
+    /// Generations rule the text doesn't record. Those are rejected.
+    pub fn from_text(text: &str) -> Result<Universe, JsValue> {
+        let (width, height, cells) = parse(text).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Universe::with_cells(width, height, cells))
+    }
+}
+
+pub fn parse(text: &str) -> Result<(u32, u32, Vec<u8>), String> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let width = lines.first().map_or(0, |line| line.chars().count());
+    if width == 0 {
+        return Err("text must contain at least one non-empty line".to_string());
+    }
+
+    let mut cells = Vec::with_capacity(width * lines.len());
+    for (number, line) in lines.iter().enumerate() {
+        let length = line.chars().count();
+        if length != width {
+            return Err(format!(
+                "line {} has {} cells but the first line has {}",
+                number + 1,
+                length,
+                width
+            ));
+        }
+        for ch in line.chars() {
+            cells.push(match ch {
+                '◼' | '#' | 'O' => ALIVE,
+                '◻' | '.' => DEAD,
+                other => {
+                    return Err(format!(
+                        "line {}: unexpected character '{}'",
+                        number + 1,
+                        other
+                    ))
+                }
+            });
+        }
+    }
+
+    let (width, height) = (width as u32, lines.len() as u32);
+    check_dimensions(width, height)?;
+    Ok((width, height, cells))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::testing::soup;
+    use crate::Universe;
+
+    #[test]
+    fn plain_renders_read_back() {
+        let universe = soup(23, 17, 0.4, 21);
+        let read = Universe::from_text(&universe.render()).unwrap();
+        assert_eq!((read.width, read.height), (23, 17));
+        assert_eq!(read.cells, universe.cells);
+
+        let mut ascii = universe.clone();
+        ascii.set_render_glyphs('#', '.');
+        assert_eq!(parse(&ascii.render()).unwrap().2, universe.cells);
+    }
+
+    #[test]
+    fn other_renders_are_rejected() {
+        let mut universe = soup(12, 12, 0.4, 22);
+        universe.set_render_border(true);
+        assert!(parse(&universe.render()).is_err());
+
+        let mut universe = soup(12, 12, 0.4, 22);
+        universe.set_render_glyphs('x', ' ');
+        assert!(parse(&universe.render()).is_err());
+
+        let mut universe = soup(12, 12, 0.4, 22);
+        universe.set_rule("B3/S23/C3").unwrap();
+        universe.tick();
+        let text = universe.render();
+        assert!(text.contains('▣'));
+        assert!(parse(&text)
+            .unwrap_err()
+            .contains("unexpected character '▣'"));
+    }
+}
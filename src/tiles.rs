@@ -0,0 +1,248 @@
+// Skipping settled parts of the grid during the per-cell loop.
+//
+// The grid is split into square tiles. Every rule the per-cell loop runs
+// looks only at a cell and its immediate neighbours, so when nothing in a
+// tile or the tiles around it changed last generation, applying the same
+// rule again leaves the tile as it is and the loop can skip it. Changes are
+// found by comparing the current cells with the previous generation still
+// held in the spare buffer. That comparison misses an edit that puts a cell
+// back the way it was a generation ago, so edits between ticks also mark
+// their tile, and edits too broad to list forget the last step altogether.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::boundary::Boundary;
+use crate::neighborhood::Neighborhood;
+use crate::rules::Rule;
+use crate::Universe;
+
+pub const TILE_SIZE: u32 = 16;
+
+// Everything besides the cells that decides what the per-cell loop computes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepConfig {
+    pub automaton: Automaton,
+    pub rule: Rule,
+    pub neighborhood: Neighborhood,
+    pub boundary: Boundary,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tiles {
+    // The settings of the last step, when it ran the per-cell loop and left
+    // the generation before it in the spare buffer.
+    pub last_step: Option<StepConfig>,
+    // Which tiles the current step visits, row by row.
+    pub active: Vec<bool>,
+    pub active_count: u32,
+    // Tiles with cells edited since the last step, row by row.
+    edited: Vec<bool>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// How many 16x16 tiles the last tick computed. Tiles where nothing
+    /// changed nearby are skipped; the first tick after a change of rule or
+    /// size computes them all.
+    pub fn active_tile_count(&self) -> u32 {
+        self.tiles.active_count
+    }
+}
+
+impl Universe {
+    pub(crate) fn step_config(&self) -> StepConfig {
+        StepConfig {
+            automaton: self.automaton,
+            rule: self.rule,
+            neighborhood: self.neighborhood,
+            boundary: self.boundary,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    // Work out which tiles this step must visit, given the settings of the
+    // previous step. Every tile is active unless that step ran the per-cell
+    // loop with the same settings.
+    pub(crate) fn mark_active_tiles(&mut self, previous: Option<StepConfig>) {
+        let across = self.width.div_ceil(TILE_SIZE);
+        let down = self.height.div_ceil(TILE_SIZE);
+        let mut active = std::mem::take(&mut self.tiles.active);
+        active.clear();
+
+        let tiles = (across * down) as usize;
+        let mut edited = std::mem::take(&mut self.tiles.edited);
+        if previous != Some(self.step_config()) {
+            active.resize(tiles, true);
+        } else {
+            active.resize(tiles, false);
+            // Tiles with cells that changed last generation join the edited
+            // ones, so each is spread to its neighbours once.
+            edited.resize(tiles, false);
+            let width = self.width as usize;
+            for (idx, _) in self
+                .cells
+                .iter()
+                .zip(&self.next)
+                .enumerate()
+                .filter(|(_, (now, was))| now != was)
+            {
+                let (row, col) = ((idx / width) as u32, (idx % width) as u32);
+                edited[(row / TILE_SIZE * across + col / TILE_SIZE) as usize] = true;
+            }
+            let changed = edited
+                .iter()
+                .enumerate()
+                .filter(|(_, &edited)| edited)
+                .map(|(tile, _)| (tile as u32 / across, tile as u32 % across));
+            for (tile_row, tile_col) in changed {
+                for delta_row in -1..=1 {
+                    for delta_col in -1..=1 {
+                        let r = self.tile_neighbor(i64::from(tile_row) + delta_row, down);
+                        let c = self.tile_neighbor(i64::from(tile_col) + delta_col, across);
+                        if let (Some(r), Some(c)) = (r, c) {
+                            active[(r * across + c) as usize] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        edited.fill(false);
+        self.tiles.edited = edited;
+        self.tiles.active_count = active.iter().filter(|&&a| a).count() as u32;
+        self.tiles.active = active;
+    }
+
+    // Note that the cell at `idx` was edited outside the per-cell loop, so
+    // the next step visits its tile even if it now matches the generation
+    // before.
+    pub(crate) fn mark_edited(&mut self, idx: usize) {
+        let across = self.width.div_ceil(TILE_SIZE);
+        let tiles = (across * self.height.div_ceil(TILE_SIZE)) as usize;
+        let edited = &mut self.tiles.edited;
+        if edited.len() != tiles {
+            edited.clear();
+            edited.resize(tiles, false);
+        }
+        let width = self.width as usize;
+        let tile = (idx / width) as u32 / TILE_SIZE * across + (idx % width) as u32 / TILE_SIZE;
+        edited[tile as usize] = true;
+    }
+
+    // The tile at `position` along an axis of `count` tiles. Only a torus
+    // joins the tiles at opposite edges; a mirror reflects each edge cell
+    // onto itself, inside the same tile.
+    fn tile_neighbor(&self, position: i64, count: u32) -> Option<u32> {
+        match self.boundary {
+            Boundary::Torus => Some(position.rem_euclid(i64::from(count)) as u32),
+            _ => u32::try_from(position).ok().filter(|&p| p < count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::Rng;
+    use crate::testing::{empty, reference_step, soup};
+    use crate::{Universe, ALIVE};
+
+    // Tick and check the result against the reference stepper.
+    fn tick_and_check(universe: &mut Universe) {
+        let expected = reference_step(&universe.cells, universe.width, universe.height);
+        universe.tick();
+        assert_eq!(
+            universe.cells, expected,
+            "generation {}",
+            universe.generation
+        );
+    }
+
+    #[test]
+    fn edit_undoing_the_last_generation_is_stepped() {
+        let mut universe = empty(20, 20);
+        universe.set_cells_alive(&[5, 5, 5, 6, 6, 5]).unwrap();
+        universe.tick();
+        assert_eq!(universe.population(), 4);
+
+        // The block's fourth cell was born last generation; killing it
+        // leaves the board as it was before, which must still step.
+        universe.toggle_cell(6, 6);
+        assert_eq!(universe.population(), 3);
+        tick_and_check(&mut universe);
+        assert_eq!(universe.population(), 4);
+    }
+
+    #[test]
+    fn glider_crossing_tile_boundaries() {
+        let mut universe = empty(96, 96);
+        // It travels a quarter cell a generation, across two tile edges.
+        universe.set_pattern("glider", 12, 12).unwrap();
+        tick_and_check(&mut universe);
+        for _ in 0..120 {
+            tick_and_check(&mut universe);
+            // Only the glider's tiles and their neighbours are stepped.
+            assert!(universe.active_tile_count() <= 16);
+        }
+        assert_eq!(universe.population(), 5);
+    }
+
+    #[test]
+    fn oscillator_straddling_four_tiles() {
+        let mut universe = empty(64, 64);
+        // The beacon's two blocks sit either side of the corner where four
+        // tiles meet.
+        universe.set_pattern("beacon", 14, 14).unwrap();
+        let start = universe.cells.clone();
+        tick_and_check(&mut universe);
+        for _ in 0..9 {
+            tick_and_check(&mut universe);
+            // The tiles around the two blinking cells, which lie in
+            // diagonally opposite tiles, but not the far ones.
+            assert_eq!(universe.active_tile_count(), 14);
+        }
+        assert_eq!(universe.cells, start);
+    }
+
+    #[test]
+    fn random_edits_between_ticks_match_reference() {
+        let mut universe = soup(50, 40, 0.3, 1);
+        let mut rng = Rng::new(2);
+        let mut pick = |below: u32| (rng.next_f64() * f64::from(below)) as u32;
+        for step in 0..400 {
+            // The cells the last tick changed, which are the edits a
+            // comparison with the generation before can't see undone.
+            let changed: Vec<usize> = (0..universe.cells.len())
+                .filter(|&idx| universe.cells[idx] != universe.next[idx])
+                .collect();
+            for _ in 0..pick(6) {
+                let (row, col) = (pick(40), pick(50));
+                match pick(5) {
+                    0 => universe.toggle_cell(row, col),
+                    1 => universe.set_cells_alive(&[row, col]).unwrap(),
+                    2 => universe.set_pattern("glider", row, col).unwrap(),
+                    3 => universe.set_cells_dead(&[row, col]).unwrap(),
+                    _ if !changed.is_empty() => {
+                        let idx = changed[pick(changed.len() as u32) as usize];
+                        universe.toggle_cell(idx as u32 / 50, idx as u32 % 50);
+                    }
+                    _ => {}
+                }
+            }
+            if step % 13 == 12 {
+                // Undo the whole of the last generation by hand.
+                for &idx in &changed {
+                    universe.toggle_cell(idx as u32 / 50, idx as u32 % 50);
+                }
+            }
+            if step % 97 == 96 {
+                universe.shift(7, -3);
+            }
+            tick_and_check(&mut universe);
+        }
+        assert!(universe.cells.contains(&ALIVE));
+    }
+}
@@ -0,0 +1,135 @@
+// Ghost trails behind cells that have just died, for fading them out when
+// drawing.
+//
+// The trail is only for display: the rule never sees it.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, ALIVE};
+
+// How a live cell shows in `display_states`.
+const SHOWN_ALIVE: u8 = 255;
+
+// Fading cells and the buffer `display_states` fills.
+#[derive(Clone, Debug, Default)]
+pub struct Trail {
+    // Generations a trail lasts; none are kept while this is 0.
+    length: u8,
+    // How many more generations each dead cell's trail lasts.
+    decay: Vec<u8>,
+    display: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Leave a trail behind cells that die, lasting `n` generations, for
+    /// `display_states`. 0, the default, turns trails off and forgets them.
+    /// Trails are never seen by the rule, so they don't change how the
+    /// board evolves.
+    pub fn set_trail_length(&mut self, n: u8) {
+        let trail = &mut self.trail;
+        trail.length = n;
+        for decay in &mut trail.decay {
+            *decay = (*decay).min(n);
+        }
+        if n == 0 {
+            trail.decay = Vec::new();
+            trail.display = Vec::new();
+        }
+    }
+
+    /// Pointer to the cells as they should be drawn with trails, one byte
+    /// per cell in row-major order: 255 for a live cell, `n` for a cell that
+    /// died in the last generation, one less for each generation since,
+    /// down to 0. Trails start over whenever the universe changes size.
+    ///
+    /// Like `cells`, read it again after every call that may change the
+    /// board.
+    pub fn display_states(&mut self) -> *const u8 {
+        self.fit_trail();
+        let trail = &mut self.trail;
+        trail.display.clear();
+        trail
+            .display
+            .extend(self.cells.iter().enumerate().map(|(idx, &state)| {
+                if state == ALIVE {
+                    SHOWN_ALIVE
+                } else {
+                    trail.decay.get(idx).copied().unwrap_or(0)
+                }
+            }));
+        trail.display.as_ptr()
+    }
+}
+
+impl Universe {
+    // Start the trails over if the universe has changed size since.
+    pub(crate) fn fit_trail(&mut self) {
+        let trail = &mut self.trail;
+        if trail.length > 0 && trail.decay.len() != self.cells.len() {
+            trail.decay.clear();
+            trail.decay.resize(self.cells.len(), 0);
+        }
+    }
+}
+
+impl Trail {
+    // Fade the trails by a generation and start new ones behind the cells
+    // that died in it.
+    pub fn record(&mut self, before: &[u8], after: &[u8]) {
+        if self.length == 0 {
+            return;
+        }
+        for ((decay, &was), &now) in self.decay.iter_mut().zip(before).zip(after) {
+            *decay = if now == ALIVE {
+                0
+            } else if was == ALIVE {
+                self.length
+            } else {
+                decay.saturating_sub(1)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SHOWN_ALIVE;
+    use crate::testing::{empty, soup};
+
+    #[test]
+    fn trails_leave_the_ticks_alone() {
+        for rule in ["B3/S23", "B3/S23/C4", "B36/S23"] {
+            let mut plain = soup(48, 32, 0.35, 18);
+            plain.set_rule(rule).unwrap();
+            let mut trailed = plain.clone();
+            trailed.set_trail_length(6);
+            let mut faded = false;
+            for _ in 0..60 {
+                plain.tick();
+                trailed.tick();
+                trailed.display_states();
+                faded |= trailed.trail.decay.iter().any(|&decay| decay > 0);
+                assert_eq!(trailed.cells, plain.cells, "{rule}");
+                assert_eq!(trailed.state_hash(), plain.state_hash());
+            }
+            assert!(faded, "{rule}");
+        }
+    }
+
+    #[test]
+    fn trails_fade_a_generation_at_a_time() {
+        let mut universe = empty(6, 6);
+        universe.set_trail_length(3);
+        universe.toggle_cell(1, 1);
+        universe.set_pattern("block", 3, 3).unwrap();
+        let block = universe.get_index(3, 3);
+        let lone = universe.get_index(1, 1);
+        for shown in [3, 2, 1, 0, 0] {
+            universe.tick();
+            universe.display_states();
+            assert_eq!(universe.trail.display[lone], shown);
+            assert_eq!(universe.trail.display[block], SHOWN_ALIVE);
+        }
+    }
+}
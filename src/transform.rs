@@ -0,0 +1,224 @@
+// Moving and flipping the contents of the whole universe at once.
+
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Move every cell down by `delta_row` rows and right by `delta_col`
+    /// columns, wrapping around the edges. Negative deltas move up or left,
+    /// and deltas larger than the universe wrap around more than once.
+    ///
+    /// Shifting by the velocity of a spaceship each period keeps it in view.
+    /// Ants move along with the cells.
+    pub fn shift(&mut self, delta_row: i32, delta_col: i32) {
+        let width = self.width as usize;
+        let rows = i64::from(delta_row).rem_euclid(i64::from(self.height)) as u32;
+        let cols = i64::from(delta_col).rem_euclid(i64::from(self.width)) as u32;
+        self.remember();
+
+        let size = self.cells.len();
+        shifted(&mut self.cells, width, rows, cols);
+        self.cells_rewritten();
+        // A buffer of another size is out of date and will be started
+        // afresh.
+        if let Some(ages) = self.ages.as_mut().filter(|ages| ages.len() == size) {
+            shifted(ages, width, rows, cols);
+        }
+        if let Some(heat) = self.heatmap.as_mut().filter(|heat| heat.len() == size) {
+            shifted(heat, width, rows, cols);
+        }
+        for ant in &mut self.ants {
+            ant.row = (ant.row + rows) % self.height;
+            ant.col = (ant.col + cols) % self.width;
+        }
+    }
+
+    /// Flip every cell between alive and dead, as `toggle_cell` would.
+    /// Dying cells come to life.
+    pub fn invert(&mut self) {
+        self.remember();
+        for idx in 0..self.cells.len() {
+            let state = self.toggled_at(idx);
+            self.set_state(idx, state);
+        }
+    }
+
+    /// Reflect the board left to right when `horizontal` is set, otherwise
+    /// top to bottom. Ants move and turn along with the cells.
+    pub fn mirror(&mut self, horizontal: bool) {
+        self.remember();
+        let (width, height) = (self.width, self.height);
+        let size = self.cells.len();
+        mirrored(&mut self.cells, width as usize, horizontal);
+        self.cells_rewritten();
+        // As in `shift`, a buffer of another size is left to be started
+        // afresh.
+        if let Some(ages) = self.ages.as_mut().filter(|ages| ages.len() == size) {
+            mirrored(ages, width as usize, horizontal);
+        }
+        if let Some(heat) = self.heatmap.as_mut().filter(|heat| heat.len() == size) {
+            mirrored(heat, width as usize, horizontal);
+        }
+        for ant in &mut self.ants {
+            // Ants may be left outside the grid after it shrinks.
+            (ant.row, ant.col) = (ant.row % height, ant.col % width);
+            if horizontal {
+                ant.col = width - 1 - ant.col;
+                // East and west swap.
+                if ant.direction % 2 == 1 {
+                    ant.direction ^= 2;
+                }
+            } else {
+                ant.row = height - 1 - ant.row;
+                if ant.direction % 2 == 0 {
+                    ant.direction ^= 2;
+                }
+            }
+        }
+    }
+
+    /// Turn the board a quarter turn clockwise, so the left column becomes
+    /// the top row. The width and height swap, so on a board that isn't
+    /// square `cells` moves to a new buffer of a different shape: read the
+    /// pointer, width and height again afterwards. Ants move and turn along
+    /// with the cells.
+    pub fn rotate_cw(&mut self) {
+        self.remember();
+        let (width, height) = (self.width, self.height);
+        let size = self.cells.len();
+        let cells = rotated(&self.cells, width as usize, height as usize);
+        let ages = self
+            .ages
+            .as_ref()
+            .filter(|ages| ages.len() == size)
+            .map(|ages| rotated(ages, width as usize, height as usize));
+        let heat = self
+            .heatmap
+            .as_ref()
+            .filter(|heat| heat.len() == size)
+            .map(|heat| rotated(heat, width as usize, height as usize));
+        for ant in &mut self.ants {
+            let (row, col) = (ant.row % height, ant.col % width);
+            (ant.row, ant.col) = (col, height - 1 - row);
+            ant.direction = (ant.direction + 1) % 4;
+        }
+
+        self.width = height;
+        self.height = width;
+        self.replace_cells(cells);
+        if let Some(ages) = ages {
+            self.ages = Some(ages);
+        }
+        if let Some(heat) = heat {
+            self.heatmap = Some(heat);
+        }
+        self.fit_heatmap();
+        self.resized |= width != height;
+    }
+}
+
+// Move a board's cells down `rows` rows and right `cols` columns, wrapping
+// around the edges.
+fn shifted<T>(cells: &mut [T], width: usize, rows: u32, cols: u32) {
+    cells.rotate_right(rows as usize * width);
+    if cols != 0 {
+        for row in cells.chunks_mut(width) {
+            row.rotate_right(cols as usize);
+        }
+    }
+}
+
+// Reflect a board stored in rows of `width` in place.
+fn mirrored<T>(cells: &mut [T], width: usize, horizontal: bool) {
+    if horizontal {
+        for row in cells.chunks_mut(width) {
+            row.reverse();
+        }
+        return;
+    }
+    let height = cells.len() / width;
+    for top in 0..height / 2 {
+        let bottom = height - 1 - top;
+        let (upper, lower) = cells.split_at_mut(bottom * width);
+        upper[top * width..(top + 1) * width].swap_with_slice(&mut lower[..width]);
+    }
+}
+
+// A `width` by `height` board turned a quarter turn clockwise, as `height`
+// by `width`.
+fn rotated<T: Copy>(cells: &[T], width: usize, height: usize) -> Vec<T> {
+    // Row `r` of the result is column `r` of the board read bottom to top.
+    (0..width)
+        .flat_map(|row| (0..height).rev().map(move |col| cells[col * width + row]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{empty, soup};
+    use crate::{Universe, ALIVE};
+
+    // The live cells, row by row.
+    fn live(universe: &Universe) -> Vec<(u32, u32)> {
+        (0..universe.height)
+            .flat_map(|row| (0..universe.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| universe.cells[universe.get_index(row, col)] == ALIVE)
+            .collect()
+    }
+
+    #[test]
+    fn negative_and_oversized_deltas_wrap() {
+        let mut universe = empty(10, 7);
+        universe.set_cells_alive(&[1, 2]).unwrap();
+        universe.shift(-3, 24);
+        assert_eq!(live(&universe), [(5, 6)]);
+        universe.shift(7 * 5 + 2, -10 * 3 - 6);
+        assert_eq!(live(&universe), [(0, 0)]);
+        universe.shift(i32::MIN, i32::MAX);
+        let (rows, cols) = (i32::MIN.rem_euclid(7), i32::MAX.rem_euclid(10));
+        assert_eq!(live(&universe), [(rows as u32, cols as u32)]);
+    }
+
+    #[test]
+    fn ages_and_heat_move_with_the_cells() {
+        let mut universe = soup(12, 9, 0.4, 15);
+        universe.set_track_ages(true);
+        universe.enable_heatmap(true);
+        universe.tick_n(5);
+        let index = |row: u32, col: u32| (row * 12 + col) as usize;
+        let (ages, heat) = (
+            universe.ages.clone().unwrap(),
+            universe.heatmap.clone().unwrap(),
+        );
+
+        universe.shift(-4, 15);
+        for row in 0..9 {
+            for col in 0..12 {
+                let moved = index((row + 5) % 9, (col + 3) % 12);
+                assert_eq!(
+                    universe.ages.as_ref().unwrap()[moved],
+                    ages[index(row, col)]
+                );
+                assert_eq!(
+                    universe.heatmap.as_ref().unwrap()[moved],
+                    heat[index(row, col)]
+                );
+            }
+        }
+    }
+    #[test]
+    fn stale_heat_is_started_afresh_rather_than_turned() {
+        let mut universe = soup(10, 10, 0.4, 16);
+        universe.enable_heatmap(true);
+        universe.tick_n(3);
+        universe.resize(20, 20, "top_left").unwrap();
+        universe.mirror(true);
+        universe.mirror(false);
+        universe.rotate_cw();
+        assert_eq!(universe.heatmap.as_ref().unwrap(), &vec![0; 400]);
+        universe.tick();
+        assert_eq!(universe.heatmap.as_ref().unwrap().len(), 400);
+    }
+}
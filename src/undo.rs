@@ -0,0 +1,168 @@
+// Undoing and redoing changes to the board.
+//
+// While the history is on, every operation that changes the cells saves a
+// copy of the board first. Copies are packed a bit per cell whenever every
+// cell is dead or alive, so a 256x256 board costs 8KB per step.
+
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::population::Changes;
+use crate::{Universe, ALIVE, DEAD};
+
+// A copy of the board: its size, generation and cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedBoard {
+    pub width: u32,
+    pub height: u32,
+    pub generation: u64,
+    // One bit per cell, least significant first, when every cell is dead or
+    // alive; otherwise one byte per cell.
+    pub packed: bool,
+    pub data: Vec<u8>,
+}
+
+impl SavedBoard {
+    // Whether this holds the same cells as another board, whatever the
+    // generation.
+    fn same_cells(&self, other: &SavedBoard) -> bool {
+        (self.width, self.height, self.packed) == (other.width, other.height, other.packed)
+            && self.data == other.data
+    }
+
+    // The cells, a byte each.
+    pub fn cells(&self) -> Vec<u8> {
+        let size = self.width as usize * self.height as usize;
+        if !self.packed {
+            return self.data.clone();
+        }
+        (0..size)
+            .map(|idx| {
+                if self.data[idx / 8] >> (idx % 8) & 1 == 1 {
+                    ALIVE
+                } else {
+                    DEAD
+                }
+            })
+            .collect()
+    }
+}
+
+// Boards saved before each change, most recent last, and those undone since
+// the last change.
+#[derive(Clone, Debug, Default)]
+pub struct UndoHistory {
+    capacity: usize,
+    undo: VecDeque<SavedBoard>,
+    redo: Vec<SavedBoard>,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Keep up to `capacity` earlier boards for `undo`, dropping the oldest
+    /// once full. Off (0) by default; turning it off forgets the history.
+    ///
+    /// Ticks, edits, pattern placement, loading, clearing, randomizing and
+    /// resizing can all be undone. Only the cells, size and generation are
+    /// restored, not the rule or any ants.
+    pub fn set_history_capacity(&mut self, capacity: u32) {
+        let history = &mut self.undo;
+        history.capacity = capacity as usize;
+        while history.undo.len() > history.capacity {
+            history.undo.pop_front();
+        }
+        if history.capacity == 0 {
+            history.redo.clear();
+        }
+    }
+
+    /// The number of steps that can be undone.
+    pub fn history_len(&self) -> u32 {
+        self.undo.undo.len() as u32
+    }
+
+    /// Go back to the board before the last change. Returns false when
+    /// there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(board) = self.undo.undo.pop_back() else {
+            return false;
+        };
+        let current = self.save_board();
+        self.undo.redo.push(current);
+        self.load_board(&board);
+        true
+    }
+
+    /// Reapply the last change undone. Returns false when there is nothing
+    /// to redo, including after any new change following an undo.
+    pub fn redo(&mut self) -> bool {
+        let Some(board) = self.undo.redo.pop() else {
+            return false;
+        };
+        let current = self.save_board();
+        self.undo.undo.push_back(current);
+        self.load_board(&board);
+        true
+    }
+}
+
+impl Universe {
+    // Save the board before a change, if the history is on. Nested
+    // operations save only once, since the board hasn't changed in between.
+    pub(crate) fn remember(&mut self) {
+        if self.undo.capacity == 0 {
+            return;
+        }
+        let board = self.save_board();
+        self.undo.redo.clear();
+        if let Some(last) = self.undo.undo.back() {
+            if last.same_cells(&board) {
+                return;
+            }
+        }
+        if self.undo.undo.len() == self.undo.capacity {
+            self.undo.undo.pop_front();
+        }
+        self.undo.undo.push_back(board);
+    }
+
+    pub(crate) fn save_board(&self) -> SavedBoard {
+        let mut board = SavedBoard {
+            width: 0,
+            height: 0,
+            generation: 0,
+            packed: false,
+            data: Vec::new(),
+        };
+        self.save_board_into(&mut board);
+        board
+    }
+
+    // Like `save_board`, overwriting a board that is no longer needed so
+    // its allocation is reused.
+    pub(crate) fn save_board_into(&self, board: &mut SavedBoard) {
+        let packed = self.cells.iter().all(|&cell| cell == DEAD || cell == ALIVE);
+        board.width = self.width;
+        board.height = self.height;
+        board.generation = self.generation;
+        board.packed = packed;
+        if packed {
+            self.pack_live_into(&mut board.data);
+        } else {
+            board.data.clone_from(&self.cells);
+        }
+    }
+
+    // Put back a saved board, resizing the universe to match.
+    pub(crate) fn load_board(&mut self, board: &SavedBoard) {
+        if (board.width, board.height) != (self.width, self.height) {
+            self.width = board.width;
+            self.height = board.height;
+            self.resized = true;
+        }
+        self.replace_cells(board.cells());
+        self.generation = board.generation;
+        self.changes = Changes::default();
+    }
+}
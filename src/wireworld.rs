@@ -0,0 +1,134 @@
+// Wireworld, where electrons travel along wires of conductor.
+//
+// Cells are empty, electron heads, electron tails or conductors. Heads become
+// tails, tails become conductors, and a conductor becomes a head when one or
+// two of its neighbours are heads. Heads share the live state's value, so the
+// usual live neighbour count is the number of neighbouring heads.
+
+use wasm_bindgen::prelude::*;
+
+use crate::automaton::Automaton;
+use crate::pattern::{self, Pattern};
+use crate::{PlacementMode, Universe, ALIVE, DEAD};
+
+pub const EMPTY: u8 = DEAD;
+pub const HEAD: u8 = ALIVE;
+pub const TAIL: u8 = 2;
+pub const CONDUCTOR: u8 = 3;
+pub const STATES: u8 = 4;
+
+// Circuits available to `set_pattern` in Wireworld, drawn with `#` for
+// conductor, `H` for an electron head, `t` for a tail and `.` for empty.
+#[rustfmt::skip]
+static CIRCUITS: &[(&str, &[&str])] = &[
+    // An electron circling a loop, sending a pulse down the wire to the
+    // right every 8 generations.
+    ("clock", &[
+        ".tH#.......",
+        "#...#######",
+        ".###.......",
+    ]),
+    // Passes electrons travelling from left to right and blocks those
+    // travelling the other way.
+    ("diode", &[
+        "...##....",
+        "####.####",
+        "...##....",
+    ]),
+];
+
+#[wasm_bindgen]
+impl Universe {
+    /// Lay conductor on a cell, for building Wireworld circuits.
+    ///
+    /// Throws an error unless the Wireworld automaton is selected, or if the
+    /// cell is outside the universe.
+    pub fn set_conductor(&mut self, row: u32, column: u32) -> Result<(), JsValue> {
+        self.set_wire(row, column, CONDUCTOR)
+    }
+
+    /// Put an electron head on a cell. A lone head on a wire sends
+    /// electrons both ways along it.
+    ///
+    /// Throws an error unless the Wireworld automaton is selected, or if the
+    /// cell is outside the universe.
+    pub fn place_electron(&mut self, row: u32, column: u32) -> Result<(), JsValue> {
+        self.set_wire(row, column, HEAD)
+    }
+}
+
+impl Universe {
+    fn set_wire(&mut self, row: u32, column: u32, state: u8) -> Result<(), JsValue> {
+        if self.automaton != Automaton::Wireworld {
+            return Err(JsValue::from_str(
+                "Wireworld cells need the wireworld automaton; call set_automaton(\"wireworld\") first",
+            ));
+        }
+        let idx = self.checked_index(row, column)?;
+        self.remember();
+        self.set_state(idx, state);
+        Ok(())
+    }
+
+    // Place a circuit's cells, which are listed in the same order as their
+    // `states`, after clearing its bounding box.
+    pub(crate) fn place_circuit(
+        &mut self,
+        circuit: &Pattern,
+        states: &[u8],
+        start_row: u32,
+        start_col: u32,
+    ) {
+        self.place(circuit, start_row, start_col, PlacementMode::Replace);
+        for (&(row, col), &state) in circuit.cells.iter().zip(states) {
+            if let Some(idx) = self.offset_index(start_row.into(), start_col.into(), row, col) {
+                self.set_state(idx, state);
+            }
+        }
+    }
+}
+
+// The next state of a cell with `heads` neighbouring electron heads.
+pub fn next(state: u8, heads: u8) -> u8 {
+    match state {
+        HEAD => TAIL,
+        TAIL => CONDUCTOR,
+        CONDUCTOR if heads == 1 || heads == 2 => HEAD,
+        other => other,
+    }
+}
+
+// Toggling lays or removes conductor; a cell carrying an electron is cleared.
+pub fn toggled(state: u8) -> u8 {
+    if state == EMPTY {
+        CONDUCTOR
+    } else {
+        EMPTY
+    }
+}
+
+// Look up a circuit by name, as a pattern of its non-empty cells and the
+// state of each of those cells.
+pub fn circuit(name: &str) -> Option<(Pattern, Vec<u8>)> {
+    let &(_, rows) = CIRCUITS.iter().find(|(n, _)| pattern::same_name(n, name))?;
+    let mut cells = Vec::new();
+    let mut states = Vec::new();
+    for (row, line) in rows.iter().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            let state = match ch {
+                '#' => CONDUCTOR,
+                'H' => HEAD,
+                't' => TAIL,
+                _ => continue,
+            };
+            cells.push((row as u32, col as u32));
+            states.push(state);
+        }
+    }
+    let pattern = Pattern {
+        width: rows[0].len() as u32,
+        height: rows.len() as u32,
+        cells,
+    };
+    Some((pattern, states))
+}